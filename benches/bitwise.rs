@@ -0,0 +1,159 @@
+//! Bitwise operations on a 1 MiB buffer against the implementations of xbits 0.9.0 they replaced.
+//! Run by `cargo bench --bench bitwise`, the `be_xor` case is expected to be at least 4x faster.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use xbits::core::Bitwise;
+
+const SIZE: usize = 1 << 20;
+const ROUNDS: usize = 200;
+
+/// The implementations of xbits 0.9.0
+mod before {
+    pub fn not(data: &mut [u8]) {
+        data.iter_mut().for_each(|a| *a = !*a);
+    }
+
+    pub fn be_or(data: &mut [u8], other: &[u8]) {
+        data.iter_mut()
+            .rev()
+            .zip(other.iter().rev())
+            .for_each(|(a, b)| *a |= *b);
+    }
+
+    pub fn be_and(data: &mut [u8], other: &[u8]) {
+        data.iter_mut()
+            .rev()
+            .zip(other.iter().rev().chain(std::iter::repeat(&0)))
+            .for_each(|(a, b)| *a &= *b);
+    }
+
+    pub fn be_xor(data: &mut [u8], other: &[u8]) {
+        data.iter_mut()
+            .rev()
+            .zip(other.iter().rev().chain(std::iter::repeat(&0)))
+            .for_each(|(a, b)| *a ^= *b);
+    }
+
+    pub fn shl(data: &mut [u8], n: usize) -> bool {
+        let len = data.len();
+        let (n, m) = (n / 8, n % 8);
+        data.copy_within(n.., 0);
+        data[len - n..].fill(0);
+        let mut carry = 0;
+        data.iter_mut().take(len - n).rev().for_each(|v| {
+            (*v, carry) = ((*v << m) | carry, *v >> (8 - m));
+        });
+        carry != 0
+    }
+
+    pub fn shr(data: &mut [u8], n: usize) -> bool {
+        let len = data.len();
+        let (n, m) = (n / 8, n % 8);
+        data.copy_within(..len - n, n);
+        data[..n].fill(0);
+        let mut carry = 0;
+        data.iter_mut().skip(n).for_each(|v| {
+            (*v, carry) = ((*v >> m) | carry, *v << (8 - m));
+        });
+        carry != 0
+    }
+
+    pub fn all_zero(data: &[u8]) -> bool {
+        data.iter().all(|&b| b == 0)
+    }
+
+    pub fn leading_zeros(data: &[u8]) -> usize {
+        match data.iter().position(|&b| b != 0) {
+            Some(n) => n * 8 + data[n].leading_zeros() as usize,
+            None => data.len() * 8,
+        }
+    }
+
+    pub fn trailing_zeros(data: &[u8]) -> usize {
+        match data.iter().rposition(|&b| b != 0) {
+            Some(n) => (data.len() - 1 - n) * 8 + data[n].trailing_zeros() as usize,
+            None => data.len() * 8,
+        }
+    }
+}
+
+/// The average time of an operation on a copy of the data
+fn measure(data: &[u8], mut op: impl FnMut(&mut [u8])) -> Duration {
+    let mut buf = data.to_vec();
+    op(&mut buf); // warm up
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        op(black_box(&mut buf));
+    }
+    start.elapsed() / ROUNDS as u32
+}
+
+fn main() {
+    let data: Vec<u8> = (0..SIZE as u32)
+        .map(|i| (i.wrapping_mul(0x9e37_79b9) >> 24) as u8)
+        .collect();
+    let other: Vec<u8> = data.iter().map(|b| b.rotate_left(3)).collect();
+    // the scans for the zeros run through the whole buffer
+    let zeros = vec![0_u8; SIZE];
+
+    #[rustfmt::skip]
+    let cases: [(&str, Duration, Duration); 11] = [
+        ("be_xor", measure(&data, |d| before::be_xor(d, &other)), measure(&data, |d| {
+            d.bit_be_xor(&other);
+        })),
+        ("be_and", measure(&data, |d| before::be_and(d, &other)), measure(&data, |d| {
+            d.bit_be_and(&other);
+        })),
+        ("be_or", measure(&data, |d| before::be_or(d, &other)), measure(&data, |d| {
+            d.bit_be_or(&other);
+        })),
+        ("not", measure(&data, before::not), measure(&data, |d| {
+            d.bit_not();
+        })),
+        ("shl 3", measure(&data, |d| {
+            before::shl(d, black_box(3));
+        }), measure(&data, |d| {
+            d.bit_shl(black_box(3));
+        })),
+        ("shr 3", measure(&data, |d| {
+            before::shr(d, black_box(3));
+        }), measure(&data, |d| {
+            d.bit_shr(black_box(3));
+        })),
+        ("shl 13", measure(&data, |d| {
+            before::shl(d, black_box(13));
+        }), measure(&data, |d| {
+            d.bit_shl(black_box(13));
+        })),
+        ("shr 13", measure(&data, |d| {
+            before::shr(d, black_box(13));
+        }), measure(&data, |d| {
+            d.bit_shr(black_box(13));
+        })),
+        ("all_zero", measure(&zeros, |d| {
+            black_box(before::all_zero(d));
+        }), measure(&zeros, |d| {
+            black_box(d.bit_all_zero());
+        })),
+        ("lz", measure(&zeros, |d| {
+            black_box(before::leading_zeros(d));
+        }), measure(&zeros, |d| {
+            black_box(d.bit_leading_zeros());
+        })),
+        ("tz", measure(&zeros, |d| {
+            black_box(before::trailing_zeros(d));
+        }), measure(&zeros, |d| {
+            black_box(d.bit_trailing_zeros());
+        })),
+    ];
+
+    println!(
+        "{:8} {:>12} {:>12} {:>8}",
+        "1 MiB", "0.9.0", "xbits", "speedup"
+    );
+    for (name, before, after) in cases {
+        let speedup = before.as_secs_f64() / after.as_secs_f64();
+        println!("{name:8} {before:>12.2?} {after:>12.2?} {speedup:>7.1}x");
+    }
+}
@@ -0,0 +1,401 @@
+/**
+ * Bloom filters over a bit buffer in the `Lsb0` order, the layout of BIP37.
+ * - `BloomHash::Bip37`: MurmurHash3 with `nTweak`, byte-identical to the `filterload` message of Bitcoin Core
+ * - `BloomHash::Double`: double hashing `h1 + i * h2` of SipHash-2-4, any number of bits
+ */
+use crate::core::{Align, BitSet, Bitwise};
+use crate::hash::{murmur3_32, siphash24};
+use crate::varint::{CompactSize, VarInt};
+use crate::{AsBitsMut, Bits, Error, Lsb0, Result};
+use std::f64::consts::LN_2;
+
+/// The maximum number of bytes of a BIP37 filter
+const BIP37_MAX_BYTES: usize = 36_000;
+/// The maximum number of hash functions of a BIP37 filter
+const BIP37_MAX_HASHES: u32 = 50;
+
+/// The hash functions of a Bloom filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BloomHash {
+    /// MurmurHash3 with the seed `i * 0xfba4c795 + tweak` for the `i`-th function,
+    /// `flags` is the `nFlags` of BIP37, kept for the serialization
+    Bip37 { tweak: u32, flags: u8 },
+    /// Double hashing of SipHash-2-4 with the key
+    Double { key: [u8; 16] },
+}
+
+/// A Bloom filter
+/// ```
+/// # use xbits::{BloomFilter, BloomHash};
+/// let mut filter = BloomFilter::with_rate(100, 0.01, BloomHash::Double { key: [7; 16] });
+/// filter.insert(b"alice");
+/// assert!(filter.contains(b"alice"));
+/// assert!(!filter.contains(b"bob"));
+///
+/// let parsed = BloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+/// assert_eq!(parsed, filter);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Bits<Lsb0>,
+    hashes: u32, // number of the hash functions
+    hash: BloomHash,
+}
+
+impl BloomFilter {
+    /// Create an empty filter of `m` bits and `k` hash functions
+    /// # Panics
+    /// - if `BloomHash::Bip37` and `m` is not a multiple of 8
+    pub fn new(m: usize, k: u32, hash: BloomHash) -> Self {
+        if let BloomHash::Bip37 { .. } = hash {
+            assert!(m.is_multiple_of(8), "[xbits] BIP37 filter of partial bytes");
+        }
+        BloomFilter {
+            bits: Bits::new(m),
+            hashes: k,
+            hash,
+        }
+    }
+
+    /// Create an empty filter for `n` items at the false positive rate.
+    /// `BloomHash::Bip37` is sized as Bitcoin Core, in whole bytes and capped by the BIP37 limits,
+    /// `BloomHash::Double` is sized by `BloomFilter::optimal`.
+    /// ```
+    /// # use xbits::{BloomFilter, BloomHash};
+    /// let filter = BloomFilter::with_rate(3, 0.01, BloomHash::Bip37 { tweak: 0, flags: 1 });
+    /// assert_eq!((filter.len(), filter.hashes()), (24, 5));
+    /// ```
+    pub fn with_rate(n: usize, rate: f64, hash: BloomHash) -> Self {
+        let (m, k) = match hash {
+            BloomHash::Bip37 { .. } => {
+                let bits = (-1.0 / (LN_2 * LN_2) * n as f64 * rate.ln()) as usize;
+                let bytes = bits.min(BIP37_MAX_BYTES * 8) / 8;
+                let k = ((bytes * 8 / n.max(1)) as f64 * LN_2) as u32;
+                (bytes * 8, k.min(BIP37_MAX_HASHES))
+            }
+            BloomHash::Double { .. } => Self::optimal(n, rate),
+        };
+        Self::new(m, k, hash)
+    }
+
+    /// Returns the optimal `(m, k)` for `n` items at the false positive rate,
+    /// `m = -n * ln(rate) / ln(2)^2` and `k = m / n * ln(2)`
+    /// ```
+    /// # use xbits::BloomFilter;
+    /// assert_eq!(BloomFilter::optimal(1000, 0.01), (9586, 7));
+    /// ```
+    pub fn optimal(n: usize, rate: f64) -> (usize, u32) {
+        let m = (-(n.max(1) as f64) * rate.ln() / (LN_2 * LN_2))
+            .ceil()
+            .max(1.0);
+        let k = (m / n.max(1) as f64 * LN_2).round().max(1.0);
+        (m as usize, k as u32)
+    }
+
+    /// Returns the number of bits
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns true if there are no bits
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the number of the hash functions
+    #[inline(always)]
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the hash functions
+    #[inline(always)]
+    pub fn hash(&self) -> BloomHash {
+        self.hash
+    }
+
+    /// Returns the bits of the filter
+    #[inline(always)]
+    pub fn bits(&self) -> &Bits<Lsb0> {
+        &self.bits
+    }
+
+    /// Insert the item, nothing is inserted into a filter of no bits
+    pub fn insert(&mut self, item: impl AsRef<[u8]>) {
+        let indexes: Vec<usize> = self.indexes(item.as_ref()).collect();
+        indexes.into_iter().for_each(|i| self.bits.set(i, true));
+    }
+
+    /// Check if the item may be in the set, a filter of no bits matches everything as Bitcoin Core
+    pub fn contains(&self, item: impl AsRef<[u8]>) -> bool {
+        self.indexes(item.as_ref()).all(|i| self.bits.get(i))
+    }
+
+    /// Remove all items
+    pub fn clear(&mut self) {
+        self.bits.as_bits_mut().0.fill(0);
+    }
+
+    /// Returns the false positive rate at the current fill, `(ones / m) ^ k`
+    pub fn false_positive_rate(&self) -> f64 {
+        if self.is_empty() {
+            return 1.0;
+        }
+        let fill = self.bits.as_bytes().bit_count_ones() as f64 / self.len() as f64;
+        fill.powi(self.hashes as i32)
+    }
+
+    /// Returns the estimated number of the distinct items inserted, `-m / k * ln(1 - ones / m)`
+    pub fn estimated_len(&self) -> f64 {
+        let (m, k) = (self.len() as f64, self.hashes.max(1) as f64);
+        let ones = self.bits.as_bytes().bit_count_ones() as f64;
+        -m / k * (1.0 - ones / m).ln()
+    }
+
+    /// Union in place, the filter of the items of both
+    /// # Errors
+    /// - `Error::LengthMismatch` if the lengths or the hash functions are different
+    pub fn union_with(&mut self, other: &Self) -> Result<&mut Self> {
+        self.check(other)?;
+        let data = self.bits.as_bits_mut().0;
+        data.bit_union_with(other.bits.as_bytes(), Align::Exact)?;
+        Ok(self)
+    }
+
+    /// Intersection in place, it matches all common items of both
+    /// with a false positive rate no better than the filter of the common items
+    /// # Errors
+    /// - `Error::LengthMismatch` if the lengths or the hash functions are different
+    pub fn intersect_with(&mut self, other: &Self) -> Result<&mut Self> {
+        self.check(other)?;
+        let data = self.bits.as_bits_mut().0;
+        data.bit_intersect_with(other.bits.as_bytes(), Align::Exact)?;
+        Ok(self)
+    }
+
+    /// Serialize the filter.
+    /// `BloomHash::Bip37` is the `filterload` payload: `CompactSize` bytes, the bytes,
+    /// `nHashFuncs` and `nTweak` in `u32` little-endian, and `nFlags`.
+    /// `BloomHash::Double` is the `CompactSize` bits, the bytes, `k` in `u32` little-endian and the key.
+    /// ```
+    /// # use xbits::{BloomFilter, BloomHash};
+    /// let filter = BloomFilter::new(8, 1, BloomHash::Bip37 { tweak: 2, flags: 0 });
+    /// assert_eq!(filter.to_bytes(), [1, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data = self.bits.as_bytes();
+        let mut bytes = match self.hash {
+            BloomHash::Bip37 { .. } => CompactSize::to_vec(data.len() as u64),
+            BloomHash::Double { .. } => CompactSize::to_vec(self.len() as u64),
+        }
+        .unwrap();
+        bytes.extend(data);
+        bytes.extend(self.hashes.to_le_bytes());
+        match self.hash {
+            BloomHash::Bip37 { tweak, flags } => {
+                bytes.extend(tweak.to_le_bytes());
+                bytes.push(flags);
+            }
+            BloomHash::Double { key } => bytes.extend(key),
+        }
+        bytes
+    }
+
+    /// Parse a filter serialized in `BloomHash::Double`
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the data is short
+    /// - `Error::LengthMismatch` if there are bytes left
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (m, len) = CompactSize::decode(bytes)?;
+        let m = usize::try_from(m).map_err(|_| Error::Overflow)?;
+        let (data, rest) = split(&bytes[len..], m.div_ceil(8))?;
+        let (hashes, rest) = split(rest, 4)?;
+        let (key, rest) = split(rest, 16)?;
+        let hash = BloomHash::Double {
+            key: key.try_into().unwrap(),
+        };
+        Self::parsed(m, data, hashes, hash, rest)
+    }
+
+    /// Parse a BIP37 `filterload` payload
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the data is short
+    /// - `Error::LengthMismatch` if there are bytes left
+    /// - `Error::Overflow` if it is over 36,000 bytes or 50 hash functions
+    /// ```
+    /// # use xbits::{BloomFilter, BloomHash};
+    /// let filter = BloomFilter::from_bip37(&[1, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0]).unwrap();
+    /// assert_eq!(filter.hash(), BloomHash::Bip37 { tweak: 2, flags: 0 });
+    /// ```
+    pub fn from_bip37(bytes: &[u8]) -> Result<Self> {
+        let (size, len) = CompactSize::decode(bytes)?;
+        if size > BIP37_MAX_BYTES as u64 {
+            return Err(Error::Overflow);
+        }
+        let (data, rest) = split(&bytes[len..], size as usize)?;
+        let (hashes, rest) = split(rest, 4)?;
+        let (tweak, rest) = split(rest, 4)?;
+        let (flags, rest) = split(rest, 1)?;
+        let hash = BloomHash::Bip37 {
+            tweak: u32::from_le_bytes(tweak.try_into().unwrap()),
+            flags: flags[0],
+        };
+        let filter = Self::parsed(data.len() * 8, data, hashes, hash, rest)?;
+        if filter.hashes > BIP37_MAX_HASHES {
+            return Err(Error::Overflow);
+        }
+        Ok(filter)
+    }
+
+    fn parsed(m: usize, data: &[u8], hashes: &[u8], hash: BloomHash, rest: &[u8]) -> Result<Self> {
+        if !rest.is_empty() {
+            return Err(Error::LengthMismatch);
+        }
+        let mut filter = Self::new(m, u32::from_le_bytes(hashes.try_into().unwrap()), hash);
+        filter.bits.as_bits_mut().0.copy_from_slice(data);
+        Ok(filter)
+    }
+
+    fn check(&self, other: &Self) -> Result<()> {
+        if (self.len(), self.hashes, self.hash) != (other.len(), other.hashes, other.hash) {
+            return Err(Error::LengthMismatch);
+        }
+        Ok(())
+    }
+
+    /// The bit indexes of the item
+    fn indexes<'a>(&self, item: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let (m, hash) = (self.len() as u64, self.hash);
+        let (h1, h2) = match hash {
+            BloomHash::Double { key } if m > 0 => {
+                let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+                let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+                (siphash24(k0, k1, item), siphash24(k1, k0, item) | 1)
+            }
+            _ => (0, 0),
+        };
+        // no bits, no indexes
+        let k = if m == 0 { 0 } else { self.hashes };
+        (0..k).map(move |i| match hash {
+            BloomHash::Bip37 { tweak, .. } => {
+                let seed = i.wrapping_mul(0xfba4_c795).wrapping_add(tweak);
+                (murmur3_32(seed, item) as u64 % m) as usize
+            }
+            BloomHash::Double { .. } => (h1.wrapping_add((i as u64).wrapping_mul(h2)) % m) as usize,
+        })
+    }
+}
+
+/// Split the first `n` bytes
+fn split(bytes: &[u8], n: usize) -> Result<(&[u8], &[u8])> {
+    bytes.split_at_checked(n).ok_or(Error::UnexpectedEnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bip37(tweak: u32) -> BloomFilter {
+        let mut filter = BloomFilter::with_rate(3, 0.01, BloomHash::Bip37 { tweak, flags: 1 });
+        for item in [
+            "99108ad8ed9bb6274d3980bab5a85c048f0950c8",
+            "b5a2c786d9ef4658287ced5914b37a1b4aa32eee",
+            "b9300670b4c5366e95b2699e8b18bc75e5f729c5",
+        ] {
+            filter.insert(hex::decode(item).unwrap());
+        }
+        filter
+    }
+
+    #[test]
+    fn test_bip37_vectors() {
+        // the vectors of Bitcoin Core
+        let filter = bip37(0);
+        assert_eq!(hex::encode(filter.to_bytes()), "03614e9b050000000000000001");
+        assert!(filter.contains(hex::decode("99108ad8ed9bb6274d3980bab5a85c048f0950c8").unwrap()));
+        assert!(!filter.contains(hex::decode("19108ad8ed9bb6274d3980bab5a85c048f0950c8").unwrap()));
+        assert_eq!(BloomFilter::from_bip37(&filter.to_bytes()), Ok(filter));
+
+        let filter = bip37(2_147_483_649);
+        assert_eq!(hex::encode(filter.to_bytes()), "03ce4299050000000100008001");
+
+        // no bits
+        let mut filter = BloomFilter::new(0, 5, BloomHash::Bip37 { tweak: 0, flags: 0 });
+        filter.insert(b"alice");
+        assert!(filter.contains(b"bob"));
+
+        assert_eq!(
+            BloomFilter::from_bip37(&[1, 0, 1, 0, 0, 0]),
+            Err(Error::UnexpectedEnd)
+        );
+        assert_eq!(
+            BloomFilter::from_bip37(&[0, 51, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(Error::Overflow)
+        );
+        assert_eq!(
+            BloomFilter::from_bip37(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(Error::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn test_double() {
+        let hash = BloomHash::Double {
+            key: *b"0123456789abcdef",
+        };
+        let mut filter = BloomFilter::with_rate(1000, 0.01, hash);
+        assert_eq!((filter.len(), filter.hashes()), (9586, 7));
+        (0..1000_u32).for_each(|i| filter.insert(i.to_be_bytes()));
+        assert!((0..1000_u32).all(|i| filter.contains(i.to_be_bytes())));
+
+        let false_positives = (1000..11_000_u32)
+            .filter(|i| filter.contains(i.to_be_bytes()))
+            .count();
+        assert!((50..200).contains(&false_positives), "{false_positives}");
+        assert!((0.005..0.02).contains(&filter.false_positive_rate()));
+        assert!((950.0..1050.0).contains(&filter.estimated_len()));
+
+        let parsed = BloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+        assert_eq!(parsed, filter);
+        let bytes = filter.to_bytes();
+        assert_eq!(
+            BloomFilter::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::UnexpectedEnd)
+        );
+
+        filter.clear();
+        assert_eq!(filter.false_positive_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let hash = BloomHash::Double { key: [0; 16] };
+        let (mut a, mut b) = (
+            BloomFilter::new(1024, 4, hash),
+            BloomFilter::new(1024, 4, hash),
+        );
+        a.insert(b"alice");
+        a.insert(b"carol");
+        b.insert(b"bob");
+        b.insert(b"carol");
+
+        let mut union = a.clone();
+        union.union_with(&b).unwrap();
+        assert!(
+            ["alice", "bob", "carol"]
+                .iter()
+                .all(|item| union.contains(item))
+        );
+
+        a.intersect_with(&b).unwrap();
+        assert!(a.contains(b"carol"));
+        assert!(!a.contains(b"alice") && !a.contains(b"bob"));
+
+        let other = BloomFilter::new(1024, 3, hash);
+        assert_eq!(a.union_with(&other).err(), Some(Error::LengthMismatch));
+        let other = BloomFilter::new(1024, 4, BloomHash::Double { key: [1; 16] });
+        assert_eq!(a.intersect_with(&other).err(), Some(Error::LengthMismatch));
+    }
+}
@@ -0,0 +1,460 @@
+/**
+ * Universal integer codes written to `Bits` and read with a `BitCursor` at any bit position.
+ * - `Unary`: `n` one bits and a zero bit
+ * - `EliasGamma`, `EliasDelta`, `EliasOmega`: Elias codes of the positive integers
+ * - `Fibonacci`: Zeckendorf representation ended by two one bits, for the positive integers
+ * - `ExpGolomb`: k-th order Exp-Golomb, the `ue(v)` of H.264 in the order 0
+ * - `GolombRice`: the quotient in unary and the remainder in `p` bits, as in BIP158 filters
+ *
+ * Every value in the range of a code is decoded back from its encoding,
+ * a truncated input is an `Error::UnexpectedEnd` and the cursor is not moved.
+ */
+use crate::core::BitOrder;
+use crate::{BitCursor, Bits, Error, Result, assert_range};
+
+/// A bit-level code of unsigned integers
+pub trait UniversalCode {
+    /// Append the code of the value to the bits
+    /// # Errors
+    /// - `Error::Overflow` if the value is out of the code range, the bits are not changed
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()>;
+
+    /// Read a value from the cursor
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the bits end before the value
+    /// - `Error::Overflow` if the value does not fit in `u64`
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64>;
+}
+
+/// Unary code of `n >= 0`, `n` one bits and a zero bit
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{Unary, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// Unary.encode_into(3, &mut bits).unwrap();
+/// assert_eq!(bits.as_bytes(), [0b1110_0000]);
+/// assert_eq!(Unary.decode_from(&mut bits.cursor()), Ok(3));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Unary;
+
+impl UniversalCode for Unary {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        (0..value).for_each(|_| bits.push(true));
+        bits.push(false);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let mut value = 0_u64;
+            while c.read_bit()? {
+                value = value.checked_add(1).ok_or(Error::Overflow)?;
+            }
+            Ok(value)
+        })
+    }
+}
+
+/// Elias gamma code of `n >= 1`, `floor(log2(n))` zero bits and the binary of `n`
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{EliasGamma, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// EliasGamma.encode_into(5, &mut bits).unwrap();
+/// assert_eq!((bits.len(), bits.as_bytes()), (5, &[0b0010_1000][..]));
+/// assert!(EliasGamma.encode_into(0, &mut bits).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EliasGamma;
+
+impl UniversalCode for EliasGamma {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        let n = positive_log2(value)?;
+        bits.push_bits(0, n);
+        bits.push_bits(value, n + 1);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let n = read_zeros(c, u64::BITS as usize - 1)?;
+            Ok(1 << n | c.read_bits(n)?)
+        })
+    }
+}
+
+/// Elias delta code of `n >= 1`, the gamma code of `floor(log2(n)) + 1` and the binary of `n`
+/// without the leading one bit
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{EliasDelta, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// EliasDelta.encode_into(10, &mut bits).unwrap();
+/// assert_eq!((bits.len(), bits.as_bytes()), (8, &[0b0010_0010][..]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EliasDelta;
+
+impl UniversalCode for EliasDelta {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        let n = positive_log2(value)?;
+        EliasGamma.encode_into(n as u64 + 1, bits)?;
+        bits.push_bits(value, n);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let n = EliasGamma.decode_from(c)? - 1;
+            if n >= u64::BITS as u64 {
+                return Err(Error::Overflow);
+            }
+            Ok(1 << n | c.read_bits(n as usize)?)
+        })
+    }
+}
+
+/// Elias omega code of `n >= 1`, the binary of `n` prefixed recursively by the binaries of
+/// their lengths minus one, ended by a zero bit
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{EliasOmega, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// EliasOmega.encode_into(10, &mut bits).unwrap();
+/// assert_eq!((bits.len(), bits.as_bytes()), (7, &[0b1110_1000][..]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EliasOmega;
+
+impl UniversalCode for EliasOmega {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        positive_log2(value)?;
+        let mut groups = vec![];
+        let mut n = value;
+        while n > 1 {
+            groups.push(n);
+            n = n.ilog2() as u64;
+        }
+        for &group in groups.iter().rev() {
+            bits.push_bits(group, group.ilog2() as usize + 1);
+        }
+        bits.push(false);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let mut n = 1_u64;
+            while c.read_bit()? {
+                if n >= u64::BITS as u64 {
+                    return Err(Error::Overflow);
+                }
+                n = 1 << n | c.read_bits(n as usize)?;
+            }
+            Ok(n)
+        })
+    }
+}
+
+/// Fibonacci code of `n >= 1`, the Zeckendorf representation from the smallest
+/// Fibonacci number `1, 2, 3, 5, ...`, ended by an extra one bit
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{Fibonacci, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// Fibonacci.encode_into(11, &mut bits).unwrap(); // 3 + 8
+/// assert_eq!((bits.len(), bits.as_bytes()), (6, &[0b0010_1100][..]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Fibonacci;
+
+/// Fibonacci numbers from 1, 2 to the greatest one in `u64`
+const FIBONACCI: [u64; 92] = {
+    let mut fib: [u64; 2] = [1, 2];
+    let mut table = [0; 92];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = fib[0];
+        fib = [fib[1], fib[0].wrapping_add(fib[1])];
+        i += 1;
+    }
+    table
+};
+
+impl UniversalCode for Fibonacci {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        positive_log2(value)?;
+        let len = FIBONACCI.partition_point(|&f| f <= value);
+        let mut digits = [false; FIBONACCI.len()];
+        let mut rest = value;
+        for i in (0..len).rev() {
+            if FIBONACCI[i] <= rest {
+                digits[i] = true;
+                rest -= FIBONACCI[i];
+            }
+        }
+        digits[..len].iter().for_each(|&digit| bits.push(digit));
+        bits.push(true);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let (mut value, mut prev) = (0_u64, false);
+            for i in 0..=FIBONACCI.len() {
+                let digit = c.read_bit()?;
+                if digit && prev {
+                    return Ok(value);
+                }
+                if digit {
+                    // the digit after the greatest number can only end the code
+                    let fib = FIBONACCI.get(i).ok_or(Error::Overflow)?;
+                    value = value.checked_add(*fib).ok_or(Error::Overflow)?;
+                }
+                prev = digit;
+            }
+            Err(Error::Overflow)
+        })
+    }
+}
+
+/// k-th order Exp-Golomb code of `n >= 0`, the Elias gamma code of `n + 2^k` without
+/// its first `k` zero bits. The order 0 is the `ue(v)` of H.264.
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{ExpGolomb, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// ExpGolomb::new(0).encode_into(3, &mut bits).unwrap();
+/// ExpGolomb::new(1).encode_into(2, &mut bits).unwrap();
+/// assert_eq!((bits.len(), bits.as_bytes()), (9, &[0b0010_0010, 0b0000_0000][..]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExpGolomb {
+    k: usize,
+}
+
+impl ExpGolomb {
+    /// Create the code of order `k`
+    /// # Parameters
+    /// - 0 <= k <= 63
+    pub fn new(k: usize) -> Self {
+        assert_range!(k, 0, 63, "ExpGolomb");
+        ExpGolomb { k }
+    }
+}
+
+impl UniversalCode for ExpGolomb {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        // n + 2^k may have 65 bits, write its leading one bit separately
+        let x = value as u128 + (1 << self.k);
+        let n = x.ilog2() as usize;
+        bits.push_bits(0, n - self.k);
+        bits.push(true);
+        bits.push_bits(x as u64, n);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let n = read_zeros(c, u64::BITS as usize - self.k)? + self.k;
+            let x = 1_u128 << n | c.read_bits(n)? as u128;
+            u64::try_from(x - (1 << self.k)).map_err(|_| Error::Overflow)
+        })
+    }
+}
+
+/// Golomb-Rice code of `n >= 0` with the parameter `p`, the unary code of `n >> p`
+/// and the lowest `p` bits of `n`
+/// ```
+/// # use xbits::Bits;
+/// # use xbits::codes::{GolombRice, UniversalCode};
+/// let mut bits: Bits = Bits::new(0);
+/// GolombRice::new(2).encode_into(9, &mut bits).unwrap();
+/// assert_eq!((bits.len(), bits.as_bytes()), (5, &[0b1100_1000][..]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GolombRice {
+    p: usize,
+}
+
+impl GolombRice {
+    /// Create the code with the parameter `p`
+    /// # Parameters
+    /// - 0 <= p <= 63
+    pub fn new(p: usize) -> Self {
+        assert_range!(p, 0, 63, "GolombRice");
+        GolombRice { p }
+    }
+}
+
+impl UniversalCode for GolombRice {
+    fn encode_into<O: BitOrder>(&self, value: u64, bits: &mut Bits<O>) -> Result<()> {
+        Unary.encode_into(value >> self.p, bits)?;
+        bits.push_bits(value, self.p);
+        Ok(())
+    }
+
+    fn decode_from<O: BitOrder>(&self, cursor: &mut BitCursor<'_, O>) -> Result<u64> {
+        cursor.attempt(|c| {
+            let q = Unary.decode_from(c)?;
+            if q > u64::MAX >> self.p {
+                return Err(Error::Overflow);
+            }
+            Ok(q << self.p | c.read_bits(self.p)?)
+        })
+    }
+}
+
+/// `floor(log2(value))` of a value in the range of the positive integer codes
+#[inline(always)]
+fn positive_log2(value: u64) -> Result<usize> {
+    match value {
+        0 => Err(Error::Overflow),
+        _ => Ok(value.ilog2() as usize),
+    }
+}
+
+/// Read the zero bits until a one bit, at most `max` zero bits
+fn read_zeros<O: BitOrder>(cursor: &mut BitCursor<'_, O>, max: usize) -> Result<usize> {
+    let mut zeros = 0;
+    while !cursor.read_bit()? {
+        zeros += 1;
+        if zeros > max {
+            return Err(Error::Overflow);
+        }
+    }
+    Ok(zeros)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lsb0;
+
+    fn encode(code: impl UniversalCode, value: u64) -> String {
+        let mut bits: Bits = Bits::new(0);
+        code.encode_into(value, &mut bits).unwrap();
+        bits.iter().map(|b| if b { '1' } else { '0' }).collect()
+    }
+
+    fn round_trip(code: impl UniversalCode + Copy, values: &[u64]) {
+        // unaligned after a leading bit, in both orders
+        let mut bits: Bits<Lsb0> = Bits::new(0);
+        bits.push(true);
+        for &value in values {
+            code.encode_into(value, &mut bits).unwrap();
+        }
+        let mut cursor = bits.cursor();
+        cursor.skip_bits(1).unwrap();
+        for &value in values {
+            assert_eq!(code.decode_from(&mut cursor), Ok(value));
+        }
+        assert_eq!(cursor.remaining(), 0);
+
+        // truncated
+        let mut bits: Bits = Bits::new(0);
+        code.encode_into(values[values.len() - 1], &mut bits)
+            .unwrap();
+        let truncated: Bits = bits.iter().take(bits.len() - 1).collect();
+        let mut cursor = truncated.cursor();
+        assert_eq!(code.decode_from(&mut cursor), Err(Error::UnexpectedEnd));
+        assert_eq!(cursor.position(), 0);
+    }
+
+    fn values(min: u64) -> Vec<u64> {
+        let edges = (0..64).flat_map(|i| [(1_u64 << i) - 1, 1 << i, (1 << i) + 1]);
+        let mut values: Vec<u64> = (min..300).chain(edges).filter(|&v| v >= min).collect();
+        values.extend([u64::MAX - 1, u64::MAX]);
+        values
+    }
+
+    #[test]
+    fn test_codewords() {
+        assert_eq!(encode(Unary, 0), "0");
+        assert_eq!(encode(EliasGamma, 1), "1");
+        assert_eq!(encode(EliasGamma, 2), "010");
+        assert_eq!(encode(EliasDelta, 1), "1");
+        assert_eq!(encode(EliasDelta, 2), "0100");
+        assert_eq!(encode(EliasDelta, 17), "001010001");
+        assert_eq!(encode(EliasOmega, 1), "0");
+        assert_eq!(encode(EliasOmega, 2), "100");
+        assert_eq!(encode(EliasOmega, 16), "10100100000");
+        assert_eq!(encode(Fibonacci, 1), "11");
+        assert_eq!(encode(Fibonacci, 4), "1011");
+        assert_eq!(encode(Fibonacci, 12), "101011");
+        assert_eq!(encode(ExpGolomb::new(0), 0), "1");
+        assert_eq!(encode(ExpGolomb::new(0), 1), "010");
+        assert_eq!(encode(ExpGolomb::new(0), 6), "00111");
+        assert_eq!(encode(ExpGolomb::new(1), 0), "10");
+        assert_eq!(encode(ExpGolomb::new(1), 2), "0100");
+        assert_eq!(encode(ExpGolomb::new(0), u64::MAX).len(), 129);
+        assert_eq!(encode(GolombRice::new(0), 2), "110");
+        assert_eq!(encode(GolombRice::new(3), 21), "110101");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(Unary, &(0..100).collect::<Vec<_>>());
+        for p in [0, 3, 19] {
+            round_trip(
+                GolombRice::new(p),
+                &(0..1000_u64)
+                    .map(|v| (v << p) | (v * 997) & ((1 << p) - 1))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        round_trip(GolombRice::new(63), &values(0));
+        round_trip(EliasGamma, &values(1));
+        round_trip(EliasDelta, &values(1));
+        round_trip(EliasOmega, &values(1));
+        round_trip(Fibonacci, &values(1));
+        for k in [0, 1, 5, 63] {
+            round_trip(ExpGolomb::new(k), &values(0));
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut bits: Bits = Bits::new(0);
+        for code in [
+            EliasGamma.encode_into(0, &mut bits),
+            Fibonacci.encode_into(0, &mut bits),
+        ] {
+            assert_eq!(code, Err(Error::Overflow));
+        }
+        assert!(bits.is_empty());
+
+        // 65 zero bits
+        let mut bits: Bits = Bits::new(0);
+        bits.push_bits(0, 64);
+        bits.push_bits(0b011, 3);
+        assert_eq!(
+            EliasGamma.decode_from(&mut bits.cursor()),
+            Err(Error::Overflow)
+        );
+        assert_eq!(
+            ExpGolomb::new(0).decode_from(&mut bits.cursor()),
+            Err(Error::Overflow)
+        );
+
+        // 2^64 + 2^64
+        let mut bits: Bits = Bits::new(0);
+        bits.push_bits(0, 64);
+        bits.push(true);
+        bits.push_bits(u64::MAX, 64);
+        assert_eq!(
+            ExpGolomb::new(0).decode_from(&mut bits.cursor()),
+            Err(Error::Overflow)
+        );
+
+        // all Fibonacci numbers
+        let mut bits: Bits = Bits::new(0);
+        (0..93).for_each(|i| bits.push(i % 2 == 0));
+        bits.push(true);
+        assert_eq!(
+            Fibonacci.decode_from(&mut bits.cursor()),
+            Err(Error::Overflow)
+        );
+    }
+}
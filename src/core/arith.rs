@@ -67,7 +67,7 @@ pub trait BitArith {
     /// let (a, b) = ([0b1100_0011, 0b0000_0001], [0b0000_0001, 0b1000_0001]);
     /// let mut x = a.clone();
     /// x.as_mut().bit_be_rem(&b);
-    /// // assert_eq!(x, (u16::from_be_bytes(a) % u16::from_be_bytes(b)).to_be_bytes());
+    /// assert_eq!(x, (u16::from_be_bytes(a) % u16::from_be_bytes(b)).to_be_bytes());
     /// ```
     fn bit_be_rem(&mut self, other: &Self::Other) -> bool;
 }
@@ -174,25 +174,22 @@ impl BitArith for [u8] {
         }
 
         let mut other = other.extend_be(self.len()); // extend to the same length
-        {
-            // Remove common trailing zeros
-            let common_divisor_bits = self.bit_trailing_zeros().min(other.bit_trailing_zeros());
-            self.bit_shr(common_divisor_bits);
-            other.bit_shr(common_divisor_bits);
-        }
+
+        // Remove common trailing zeros, restored on the remainder at last
+        let common_divisor_bits = self.bit_trailing_zeros().min(other.bit_trailing_zeros());
+        self.bit_shr(common_divisor_bits);
+        other.bit_shr(common_divisor_bits);
 
         // Perform division
-        let n = self.len();
-        let mut result = vec![0; n];
         let diff = bits_a - bits_b;
         other.bit_shl(diff);
-        for i in (0..=diff).rev() {
+        for _ in 0..=diff {
             if self.bit_be_cmp(&other) != std::cmp::Ordering::Less {
                 self.bit_be_sub(&other);
-                result[n - 1 - i / 8] |= 1 << (i % 8);
             }
             other.bit_shr(1);
         }
+        self.bit_shl(common_divisor_bits);
         false
     }
 }
@@ -223,6 +220,7 @@ impl ByteExtend for [u8] {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test_arith {
     use super::*;
 
@@ -285,4 +283,19 @@ mod test_arith {
             assert_eq!(&a, c);
         }
     }
+
+    #[test]
+    fn test_bits_rem() {
+        const TDATA: &[(&[u8], &[u8])] = &[
+            (&[0b0000_1100], &[0b0000_0101]),
+            (&[0b0011_0000, 0], &[0b0001_1000]),
+            (&[0b1100_1100], &[0, 0b0000_0011]),
+            (&[0b0000_0011], &[0b1100_1100]),
+        ];
+        for (a, b) in TDATA {
+            let mut x = a.to_vec();
+            assert_eq!(x.bit_be_rem(b), false);
+            assert_eq!(x.value(), a.value() % b.value());
+        }
+    }
 }
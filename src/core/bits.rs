@@ -1,22 +1,27 @@
-use crate::{AsBits, AsBitsMut, BitsMut, BitsRef};
+use super::{BitIterator, BitOrder, Bitwise, FromBits, Msb0};
+use crate::{AsBits, AsBitsMut, BitCursor, BitsMut, BitsRef, assert_range};
+use std::marker::PhantomData;
 
-/// A structure to represent a collection of bits.
+/// A structure to represent a collection of bits in the bit order `O`.
 /// ```
 /// # use xbits::Bits;
 /// let bits: Bits = 0b10101010_11110000_u16.into();
 /// assert_eq!(bits.len(), 16);
 /// assert_eq!(bits.capacity(), 16);
 /// ```
-pub struct Bits {
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bits<O: BitOrder = Msb0> {
     data: Vec<u8>, // Storage for bits, each byte can hold 8 bits
     len: usize,    // Number of bits stored
+    order: PhantomData<O>,
 }
 
-impl Bits {
+impl<O: BitOrder> Bits<O> {
     pub fn new(len: usize) -> Self {
         Bits {
-            data: vec![0; (len + 7) / 8],
+            data: vec![0; len.div_ceil(8)],
             len,
+            order: PhantomData,
         }
     }
 
@@ -35,15 +40,85 @@ impl Bits {
     pub fn as_bytes(&self) -> &[u8] {
         &self.data
     }
+
+    /// Get the value of the bit at `index` in the bit order
+    /// # Panics
+    /// - if `index >= len`
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "[xbits] Bits index out of range");
+        self.data.bit_get_in::<O>(index)
+    }
+
+    /// Set the value of the bit at `index` in the bit order
+    /// # Panics
+    /// - if `index >= len`
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "[xbits] Bits index out of range");
+        self.data.bit_set_in::<O>(index, value);
+    }
+
+    /// Append a bit at the end
+    /// ```
+    /// # use xbits::Bits;
+    /// let mut bits: Bits = Bits::new(0);
+    /// bits.push(true);
+    /// bits.push_bits(0b01, 2);
+    /// assert_eq!((bits.len(), bits.as_bytes()), (3, &[0b1010_0000][..]));
+    /// ```
+    pub fn push(&mut self, value: bool) {
+        if self.len == self.capacity() {
+            self.data.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// Append the lowest `n` bits of the value, the most significant bit first
+    /// # Parameters
+    /// - `n`: the number of bits to append, 0 <= n <= 64
+    pub fn push_bits(&mut self, value: u64, n: usize) {
+        assert_range!(n, 0, 64, "push_bits");
+        (0..n).rev().for_each(|i| self.push(value >> i & 1 != 0));
+    }
+
+    /// Read the bits from the first one
+    pub fn cursor(&self) -> BitCursor<'_, O> {
+        BitCursor::with_len(&self.data, self.len)
+    }
+
+    /// Iterator the bits in the bit order
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.data.bit_iter_in::<O>().take(self.len)
+    }
+}
+
+impl<O: BitOrder> FromIterator<bool> for Bits<O> {
+    /// Collect bits in the bit order
+    /// ```
+    /// # use xbits::{Bits, Lsb0};
+    /// let bits: Bits<Lsb0> = [true, false, true].into_iter().collect();
+    /// assert_eq!(bits.len(), 3);
+    /// assert_eq!(bits.as_bytes(), [0b0000_0101]);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut len = 0;
+        let data = Vec::from_bits_in::<O, _>(iter.into_iter().inspect(|_| len += 1));
+        Bits {
+            data,
+            len,
+            order: PhantomData,
+        }
+    }
 }
 
 macro_rules! impl_from {
     ($t:ty) => {
-        impl From<$t> for Bits {
+        impl<O: BitOrder> From<$t> for Bits<O> {
             fn from(value: $t) -> Self {
                 Bits {
                     data: value.to_be_bytes().to_vec(),
                     len: std::mem::size_of::<$t>() * 8,
+                    order: PhantomData,
                 }
             }
         }
@@ -64,24 +139,28 @@ impl_from!(i64);
 impl_from!(i128);
 impl_from!(isize);
 
-impl AsBits for Bits {
-    fn as_bits(&self) -> BitsRef<'_> {
-        BitsRef(&self.data)
+impl<O: BitOrder> AsBits for Bits<O> {
+    type Order = O;
+
+    fn as_bits(&self) -> BitsRef<'_, O> {
+        BitsRef::new(&self.data)
     }
 }
-impl AsBitsMut for Bits {
-    fn as_bits_mut(&mut self) -> BitsMut<'_> {
-        BitsMut(&mut self.data)
+impl<O: BitOrder> AsBitsMut for Bits<O> {
+    fn as_bits_mut(&mut self) -> BitsMut<'_, O> {
+        BitsMut::new(&mut self.data)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::core::Lsb0;
 
     #[test]
     fn test_bits_new() {
-        let bits = Bits::new(17);
+        let bits: Bits = Bits::new(17);
         assert_eq!(bits.len(), 17);
         assert_eq!(bits.capacity(), 24); // 3 bytes for 16 bits
         assert!(!bits.is_empty());
@@ -103,4 +182,25 @@ mod tests {
             vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]
         );
     }
+
+    #[test]
+    fn test_bits_order() {
+        let mut bits: Bits<Lsb0> = Bits::new(12);
+        bits.set(0, true);
+        bits.set(11, true);
+        assert_eq!(bits.as_bytes(), [0b0000_0001, 0b0000_1000]);
+        assert_eq!(bits.iter().filter(|&b| b).count(), 2);
+        assert_eq!(bits.get(11), true);
+
+        let collected: Bits<Lsb0> = bits.iter().collect();
+        assert_eq!(collected, bits);
+        let msb: Bits = bits.iter().collect();
+        assert_eq!(msb.as_bytes(), [0b1000_0000, 0b0001_0000]);
+
+        // the views keep the bit order
+        assert_eq!(bits.as_bits()[0], true);
+        assert_eq!(bits.as_bits().get(11), true);
+        bits.as_bits_mut().set(1, true);
+        assert_eq!(bits.as_bytes(), [0b0000_0011, 0b0000_1000]);
+    }
 }
@@ -1,3 +1,7 @@
+use super::iter::{read_field, write_field};
+use super::{BitOrder, Msb0};
+use std::ops::Range;
+
 /**
  * Bitwise operations implementation for `[u8]`
  */
@@ -8,7 +12,7 @@ pub trait Bitwise {
     /// - `data`: The data to be shifted
     /// - `n`: The number of bits to shift
     /// # Returns
-    /// - `true` if the leftmost `1` bits are overflowed
+    /// - `true` if any `1` bit is shifted out, `false` if only `0` bits are, even if `n` exceeds the bits
     /// # Examples
     /// ```
     /// # use xbits::core::Bitwise;
@@ -24,7 +28,7 @@ pub trait Bitwise {
     /// - `data`: The data to be shifted
     /// - `n`: The number of bits to shift
     /// # Returns
-    /// - `true` if the rightmost `1` bits are overflowed
+    /// - `true` if any `1` bit is shifted out, `false` if only `0` bits are, even if `n` exceeds the bits
     /// # Examples
     /// ```
     /// # use xbits::core::Bitwise;
@@ -132,12 +136,115 @@ pub trait Bitwise {
     /// ```
     fn bit_trailing_zeros(&self) -> usize;
 
+    /// Count the number of one bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// assert_eq!([0b0001_0000, 0b1000_0011].bit_count_ones(), 4);
+    /// ```
+    fn bit_count_ones(&self) -> usize;
+
+    /// Count the number of zero bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// assert_eq!([0b0001_0000, 0b1000_0011].bit_count_zeros(), 12);
+    /// ```
+    fn bit_count_zeros(&self) -> usize;
+
+    /// Parity of the one bits
+    /// # Returns
+    /// - `true` if the number of one bits is odd
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// assert_eq!([0b0001_0000, 0b1000_0011].bit_parity(), false);
+    /// assert_eq!([0b0001_0000, 0b1000_0001].bit_parity(), true);
+    /// ```
+    fn bit_parity(&self) -> bool;
+
+    /// Count the number of different bits for big-endian,
+    /// the shorter one is extended with leading zeros
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// assert_eq!([0b0001_0000, 0b1000_0011].bit_hamming_distance(&[0b1000_0000]), 3);
+    /// ```
+    fn bit_hamming_distance(&self, other: &Self) -> usize;
+
     /// Get the value of a specific bit
     fn bit_get(&self, index: usize) -> bool;
 
     /// Set the value of a specific bit
     fn bit_set(&mut self, index: usize, value: bool) -> &mut Self;
 
+    /// Get the value of a specific bit in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// # use xbits::{Lsb0, Msb0};
+    /// assert_eq!([0b0000_0001, 0b1000_0000].bit_get_in::<Msb0>(7), true);
+    /// assert_eq!([0b0000_0001, 0b1000_0000].bit_get_in::<Lsb0>(0), true);
+    /// assert_eq!([0b0000_0001, 0b1000_0000].bit_get_in::<Lsb0>(15), true);
+    /// ```
+    fn bit_get_in<O: BitOrder>(&self, index: usize) -> bool;
+
+    /// Set the value of a specific bit in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// # use xbits::Lsb0;
+    /// assert_eq!([0, 0].bit_set_in::<Lsb0>(9, true), [0b0000_0000, 0b0000_0010]);
+    /// ```
+    fn bit_set_in<O: BitOrder>(&mut self, index: usize, value: bool) -> &mut Self;
+
+    /// Copy `len` bits of `src` from the bit `src_offset` to the bit `offset`,
+    /// the other bits are not changed
+    /// # Panics
+    /// - if the bits are out of `self` or `src`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// let mut data = [0b1111_1111, 0b1111_1111];
+    /// data.bit_copy(3, &[0b0101_1010], 1, 6);
+    /// assert_eq!(data, [0b1111_0110, 0b1111_1111]);
+    /// ```
+    fn bit_copy(&mut self, offset: usize, src: &Self, src_offset: usize, len: usize) -> &mut Self;
+
+    /// Copy `len` bits of `src` in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// # use xbits::Lsb0;
+    /// let mut data = [0_u8; 2];
+    /// data.bit_copy_in::<Lsb0>(6, &[0b0000_1111], 0, 4);
+    /// assert_eq!(data, [0b1100_0000, 0b0000_0011]);
+    /// ```
+    fn bit_copy_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        src: &Self,
+        src_offset: usize,
+        len: usize,
+    ) -> &mut Self;
+
+    /// Copy the bits of the range `src` to the bit `dst`, the ranges may overlap, as `copy_within`
+    /// # Panics
+    /// - if the bits are out of the data
+    /// # Examples
+    /// ```
+    /// # use xbits::core::Bitwise;
+    /// let mut data = [0b1011_0000, 0b0000_0000];
+    /// data.bit_copy_within(0..4, 2);
+    /// assert_eq!(data, [0b1010_1100, 0b0000_0000]);
+    /// data.bit_copy_within(2..8, 0);
+    /// assert_eq!(data, [0b1011_0000, 0b0000_0000]);
+    /// ```
+    fn bit_copy_within(&mut self, src: Range<usize>, dst: usize) -> &mut Self;
+
+    /// Copy the bits of the range `src` to the bit `dst` in the bit order `O`
+    fn bit_copy_within_in<O: BitOrder>(&mut self, src: Range<usize>, dst: usize) -> &mut Self;
+
     /// Set all bits to `value`
     fn bit_fill(&mut self, value: bool) -> &mut Self;
 
@@ -150,44 +257,44 @@ impl Bitwise for [u8] {
         let len = self.len();
         let data = self;
         if n >= len * 8 {
+            let overflow = !data.bit_all_zero();
             data.fill(0);
-            return true;
+            return overflow;
         }
 
         let (n, m) = (n / 8, n % 8);
-        data.copy_within(n.., 0);
-        data[len - n..].fill(0);
+        let overflow = !data[..n].bit_all_zero();
+        if n > 0 {
+            data.copy_within(n.., 0);
+            data[len - n..].fill(0);
+        }
 
         if m != 0 {
-            let mut carry = 0;
-            data.iter_mut().take(len - n).rev().for_each(|v| {
-                (*v, carry) = ((*v << m) | carry, *v >> (8 - m));
-            });
-            return carry != 0;
+            return data[..len - n].shl_bits(m) || overflow;
         }
-        false
+        overflow
     }
 
     fn bit_shr(&mut self, n: usize) -> bool {
         let len = self.len();
         let data = self;
         if n >= len * 8 {
+            let overflow = !data.bit_all_zero();
             data.fill(0);
-            return true;
+            return overflow;
         }
 
         let (n, m) = (n / 8, n % 8);
-        data.copy_within(..len - n, n);
-        data[..n].fill(0);
+        let overflow = !data[len - n..].bit_all_zero();
+        if n > 0 {
+            data.copy_within(..len - n, n);
+            data[..n].fill(0);
+        }
 
         if m != 0 {
-            let mut carry = 0;
-            data.iter_mut().skip(n).for_each(|v| {
-                (*v, carry) = ((*v >> m) | carry, *v << (8 - m));
-            });
-            return carry != 0;
+            return data[n..].shr_bits(m) || overflow;
         }
-        false
+        overflow
     }
 
     fn bit_reverse(&mut self) -> &mut Self {
@@ -197,85 +304,214 @@ impl Bitwise for [u8] {
     }
 
     fn bit_not(&mut self) -> &mut Self {
+        // the compiler vectorizes the bytes loop, faster than the words
         self.iter_mut().for_each(|a| *a = !*a);
         self
     }
 
     fn bit_be_and(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .rev()
-            .zip(other.iter().rev().chain(std::iter::repeat(&0)))
-            .for_each(|(a, b)| *a &= *b);
+        let n = self.len().saturating_sub(other.len());
+        self[..n].fill(0);
+        let m = self.len() - n;
+        self[n..].zip_bytes(&other[other.len() - m..], |a, b| a & b);
         self
     }
 
     fn bit_be_or(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .rev()
-            .zip(other.iter().rev())
-            .for_each(|(a, b)| *a |= *b);
+        let m = self.len().min(other.len());
+        let n = self.len() - m;
+        self[n..].zip_bytes(&other[other.len() - m..], |a, b| a | b);
         self
     }
 
     fn bit_be_xor(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .rev()
-            .zip(other.iter().rev().chain(std::iter::repeat(&0)))
-            .for_each(|(a, b)| *a ^= *b);
+        let m = self.len().min(other.len());
+        let n = self.len() - m;
+        self[n..].zip_bytes(&other[other.len() - m..], |a, b| a ^ b);
         self
     }
 
     fn bit_le_and(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .zip(other.iter().chain(std::iter::repeat(&0)))
-            .for_each(|(a, b)| *a &= *b);
+        let m = self.len().min(other.len());
+        self[m..].fill(0);
+        self[..m].zip_bytes(&other[..m], |a, b| a & b);
         self
     }
 
     fn bit_le_or(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .zip(other.iter().chain(std::iter::repeat(&0)))
-            .for_each(|(a, b)| *a |= *b);
+        let m = self.len().min(other.len());
+        self[..m].zip_bytes(&other[..m], |a, b| a | b);
         self
     }
 
     fn bit_le_xor(&mut self, other: &Self) -> &mut Self {
-        self.iter_mut()
-            .zip(other.iter().chain(std::iter::repeat(&0)))
-            .for_each(|(a, b)| *a ^= *b);
+        let m = self.len().min(other.len());
+        self[..m].zip_bytes(&other[..m], |a, b| a ^ b);
         self
     }
 
     fn bit_all_zero(&self) -> bool {
-        self.iter().all(|&b| b == 0)
+        let words = self.chunks_exact(WORD);
+        let rem = words.remainder();
+        words.map(be_word).all(|w| w == 0) && rem.iter().all(|&b| b == 0)
     }
 
     fn bit_all_one(&self) -> bool {
-        self.iter().all(|&b| b == 0xff)
+        let words = self.chunks_exact(WORD);
+        let rem = words.remainder();
+        words.map(be_word).all(|w| w == u64::MAX) && rem.iter().all(|&b| b == 0xff)
     }
 
     fn bit_leading_zeros(&self) -> usize {
-        match self.iter().position(|&b| b != 0) {
-            Some(n) => n * 8 + self[n].leading_zeros() as usize,
+        // skip the zero words, then count in the first non-zero word or the tail bytes
+        let n = self
+            .chunks_exact(WORD)
+            .take_while(|&w| be_word(w) == 0)
+            .count()
+            * WORD;
+        match self[n..].iter().position(|&b| b != 0) {
+            Some(i) => (n + i) * 8 + self[n + i].leading_zeros() as usize,
             None => self.len() * 8,
         }
     }
 
     fn bit_trailing_zeros(&self) -> usize {
-        match self.iter().rposition(|&b| b != 0) {
-            Some(n) => (self.len() - 1 - n) * 8 + self[n].trailing_zeros() as usize,
+        // skip the zero words from the end, then count in the last non-zero word or the head bytes
+        let n = self.len()
+            - self
+                .rchunks_exact(WORD)
+                .take_while(|&w| be_word(w) == 0)
+                .count()
+                * WORD;
+        match self[..n].iter().rposition(|&b| b != 0) {
+            Some(i) => (self.len() - 1 - i) * 8 + self[i].trailing_zeros() as usize,
             None => self.len() * 8,
         }
     }
 
+    fn bit_count_ones(&self) -> usize {
+        let words = self.chunks_exact(WORD);
+        let rem = words.remainder();
+        let ones: u32 = words.map(|w| be_word(w).count_ones()).sum();
+        (ones + rem.iter().map(|b| b.count_ones()).sum::<u32>()) as usize
+    }
+
+    fn bit_count_zeros(&self) -> usize {
+        self.len() * 8 - self.bit_count_ones()
+    }
+
+    fn bit_parity(&self) -> bool {
+        let words = self.chunks_exact(WORD);
+        let rem = words.remainder();
+        let word = words.fold(0, |acc, w| acc ^ be_word(w));
+        let byte = rem.iter().fold(0, |acc, &b| acc ^ b);
+        (word.count_ones() + byte.count_ones()) % 2 == 1
+    }
+
+    fn bit_hamming_distance(&self, other: &Self) -> usize {
+        let (long, short) = match self.len() >= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        let (head, tail) = long.split_at(long.len() - short.len());
+        let words = tail.chunks_exact(WORD).zip(short.chunks_exact(WORD));
+        let rem = tail.len() / WORD * WORD;
+        let diff: u32 = words
+            .map(|(a, b)| (be_word(a) ^ be_word(b)).count_ones())
+            .sum();
+        let rem = tail[rem..].iter().zip(&short[rem..]);
+        head.bit_count_ones()
+            + (diff + rem.map(|(a, b)| (a ^ b).count_ones()).sum::<u32>()) as usize
+    }
+
     fn bit_get(&self, index: usize) -> bool {
-        self[index / 8] & (1 << (7 - index % 8)) != 0
+        self.bit_get_in::<Msb0>(index)
     }
 
     fn bit_set(&mut self, index: usize, value: bool) -> &mut Self {
+        self.bit_set_in::<Msb0>(index, value)
+    }
+
+    fn bit_get_in<O: BitOrder>(&self, index: usize) -> bool {
+        self[index / 8] & O::mask(index % 8) != 0
+    }
+
+    fn bit_set_in<O: BitOrder>(&mut self, index: usize, value: bool) -> &mut Self {
         match value {
-            true => self[index / 8] |= 1 << (7 - index % 8),
-            false => self[index / 8] &= !(1 << (7 - index % 8)),
+            true => self[index / 8] |= O::mask(index % 8),
+            false => self[index / 8] &= !O::mask(index % 8),
+        }
+        self
+    }
+
+    fn bit_copy(&mut self, offset: usize, src: &Self, src_offset: usize, len: usize) -> &mut Self {
+        self.bit_copy_in::<Msb0>(offset, src, src_offset, len)
+    }
+
+    fn bit_copy_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        src: &Self,
+        src_offset: usize,
+        len: usize,
+    ) -> &mut Self {
+        check_copy(self, offset, len);
+        check_copy(src, src_offset, len);
+        if offset % 8 == src_offset % 8 {
+            let [head, (i, bytes), tail] = aligned_parts(offset, len);
+            let (to, from) = ((offset + i) / 8, (src_offset + i) / 8);
+            self[to..to + bytes / 8].copy_from_slice(&src[from..from + bytes / 8]);
+            for (i, n) in [head, tail] {
+                let v = read_field::<O>(src, src_offset + i, n);
+                write_field::<O>(self, offset + i, n, v);
+            }
+        } else {
+            for (i, n) in chunks(len) {
+                let v = read_field::<O>(src, src_offset + i, n);
+                write_field::<O>(self, offset + i, n, v);
+            }
+        }
+        self
+    }
+
+    fn bit_copy_within(&mut self, src: Range<usize>, dst: usize) -> &mut Self {
+        self.bit_copy_within_in::<Msb0>(src, dst)
+    }
+
+    fn bit_copy_within_in<O: BitOrder>(&mut self, src: Range<usize>, dst: usize) -> &mut Self {
+        assert!(
+            src.start <= src.end,
+            "[xbits] bit copy range `{src:?}` decreasing"
+        );
+        let len = src.end - src.start;
+        check_copy(self, src.start, len);
+        check_copy(self, dst, len);
+        // the parts are copied from the end when the destination is after the source,
+        // so each part is read before it is overwritten
+        let backward = dst > src.start;
+        let move_bits = |data: &mut [u8], (i, n): (usize, usize)| {
+            let v = read_field::<O>(data, src.start + i, n);
+            write_field::<O>(data, dst + i, n, v);
+        };
+        if dst % 8 == src.start % 8 {
+            let mut parts = aligned_parts(dst, len).map(|part| (part, false));
+            parts[1].1 = true;
+            if backward {
+                parts.reverse();
+            }
+            for ((i, n), bytes) in parts {
+                match bytes {
+                    true => {
+                        let from = (src.start + i) / 8;
+                        self.copy_within(from..from + n / 8, (dst + i) / 8);
+                    }
+                    false => move_bits(self, (i, n)),
+                }
+            }
+        } else if backward {
+            chunks(len).rev().for_each(|part| move_bits(self, part));
+        } else {
+            chunks(len).for_each(|part| move_bits(self, part));
         }
         self
     }
@@ -294,15 +530,107 @@ impl Bitwise for [u8] {
     }
 }
 
+/// Bytes of a word processed at a time
+pub(crate) const WORD: usize = std::mem::size_of::<u64>();
+
+#[inline(always)]
+fn be_word(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes.try_into().unwrap())
+}
+
+/// Check the `len` bits at the bit `offset` are in the data
+#[inline(always)]
+fn check_copy(data: &[u8], offset: usize, len: usize) {
+    let end = offset.checked_add(len);
+    assert!(
+        end.is_some_and(|end| end <= data.len() * 8),
+        "[xbits] bit copy `{offset}` + `{len}` out of `{}` bits",
+        data.len() * 8
+    );
+}
+
+/// The parts `(index, bits)` of a copy of `len` bits to the bit `offset`, when the source
+/// is at the same bit of its byte: the bits before the byte boundary, the whole bytes and the rest
+#[inline(always)]
+fn aligned_parts(offset: usize, len: usize) -> [(usize, usize); 3] {
+    let head = ((8 - offset % 8) % 8).min(len);
+    let bytes = (len - head) / 8 * 8;
+    [(0, head), (head, bytes), (head + bytes, len - head - bytes)]
+}
+
+/// The parts `(index, bits)` of a copy of `len` bits by words, shifted and merged by the fields
+#[inline(always)]
+fn chunks(len: usize) -> impl DoubleEndedIterator<Item = (usize, usize)> {
+    (0..len.div_ceil(64)).map(move |i| (i * 64, (len - i * 64).min(64)))
+}
+
+pub(crate) trait BytesExtend {
+    /// Apply a bitwise operation with the other bytes of the same length
+    fn zip_bytes(&mut self, other: &[u8], op: impl Fn(u8, u8) -> u8);
+
+    /// Shift `m` bits to the left, 0 < m < 8
+    /// # Returns
+    /// - `true` if `1` bits are overflowed
+    fn shl_bits(&mut self, m: usize) -> bool;
+
+    /// Shift `m` bits to the right, 0 < m < 8
+    /// # Returns
+    /// - `true` if `1` bits are overflowed
+    fn shr_bits(&mut self, m: usize) -> bool;
+}
+
+/// The loops run forward over the bytes, which the compiler vectorizes,
+/// the loops of `u64` words measured no faster on them
+impl BytesExtend for [u8] {
+    #[inline(always)]
+    fn zip_bytes(&mut self, other: &[u8], op: impl Fn(u8, u8) -> u8) {
+        debug_assert_eq!(self.len(), other.len());
+        self.iter_mut()
+            .zip(other)
+            .for_each(|(a, &b)| *a = op(*a, b));
+    }
+
+    fn shl_bits(&mut self, m: usize) -> bool {
+        let Some(&first) = self.first() else {
+            return false;
+        };
+        // each byte takes the high bits of the next byte, which is not shifted yet
+        for i in 1..self.len() {
+            self[i - 1] = (self[i - 1] << m) | (self[i] >> (8 - m));
+        }
+        if let Some(last) = self.last_mut() {
+            *last <<= m;
+        }
+        first >> (8 - m) != 0
+    }
+
+    fn shr_bits(&mut self, m: usize) -> bool {
+        // carry the low bits of each byte to the next byte
+        let mut carry = 0;
+        for b in self.iter_mut() {
+            (*b, carry) = ((*b >> m) | carry, *b << (8 - m));
+        }
+        carry != 0
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test_offset {
     use super::Bitwise;
+    use crate::core::{BitOrder, Lsb0, Msb0};
+    use crate::test_util::{bytes, copy_bits};
 
     #[test]
     fn test_bit_shl() {
         let mut data: [u8; 2] = [0b1111_1111, 0b0000_0000];
         assert_eq!(data.bit_shl(4), true);
         assert_eq!(data, [0b1111_0000, 0b0000_0000]);
+
+        let mut data: [u8; 2] = [0b0000_0001, 0b0000_0000];
+        assert_eq!(data.bit_shl(8), true);
+        assert_eq!(data, [0b0000_0000, 0b0000_0000]);
+        assert_eq!(data.bit_shl(16), false);
     }
 
     #[test]
@@ -311,6 +639,9 @@ mod test_offset {
         assert_eq!(data.bit_shr(4), false);
         assert_eq!(data, [0b0000_1111, 0b1111_0000]);
         assert_eq!([0b1].bit_shr(1), true);
+        assert_eq!([0b1, 0b0].bit_shr(9), true);
+        assert_eq!([0b1, 0b0].bit_shr(8), false);
+        assert_eq!([0b0, 0b0].bit_shr(16), false);
     }
 
     #[test]
@@ -319,4 +650,136 @@ mod test_offset {
         data.bit_reverse();
         assert_eq!(data, [0b0000_0011, 0b0011_1111])
     }
+
+    #[test]
+    fn test_bit_order() {
+        let mut data = [0_u8; 3];
+        data.bit_set_in::<Lsb0>(0, true)
+            .bit_set_in::<Lsb0>(12, true);
+        assert_eq!(data, [0b0000_0001, 0b0001_0000, 0b0000_0000]);
+        assert_eq!(data.bit_get_in::<Msb0>(7), true);
+        assert_eq!(data.bit_get_in::<Lsb0>(12), true);
+        assert_eq!(data.bit_get_in::<Lsb0>(11), false);
+
+        data.bit_set_in::<Msb0>(7, false)
+            .bit_set_in::<Msb0>(16, true);
+        assert_eq!(data, [0b0000_0000, 0b0001_0000, 0b1000_0000]);
+        assert_eq!(data.bit_get_in::<Lsb0>(23), true);
+    }
+
+    #[test]
+    fn test_bit_count() {
+        for len in [0, 1, 7, 8, 9, 17, 33] {
+            let a = bytes(len, 0);
+            let ones: u32 = a.iter().map(|b| b.count_ones()).sum();
+            assert_eq!(a.bit_count_ones(), ones as usize);
+            assert_eq!(a.bit_count_zeros(), len * 8 - ones as usize);
+            assert_eq!(a.bit_parity(), ones % 2 == 1);
+
+            let b: Vec<u8> = a.iter().map(|&x| x ^ 0b0101).collect();
+            assert_eq!(a.bit_hamming_distance(&b), len * 2);
+            assert_eq!(a.bit_hamming_distance(&[]), ones as usize);
+            let mut c = vec![0xff; 3];
+            c.extend(&b);
+            assert_eq!(c.bit_hamming_distance(&a), 24 + len * 2);
+            assert_eq!(a.bit_hamming_distance(&c), 24 + len * 2);
+        }
+    }
+
+    #[test]
+    fn test_word_paths() {
+        // compare with the byte by byte operations on lengths around the word size
+        for len in [0, 1, 7, 8, 9, 15, 16, 17, 33] {
+            for other_len in [0, 1, 8, 9, 20] {
+                let (a, b) = (bytes(len, 1), bytes(other_len, 7));
+                let be = |op: fn(u8, u8) -> u8, pad: u8| -> Vec<u8> {
+                    let rb = b.iter().rev().copied().chain(std::iter::repeat(pad));
+                    let mut v: Vec<u8> = a.iter().rev().zip(rb).map(|(&x, y)| op(x, y)).collect();
+                    v.reverse();
+                    v
+                };
+                assert_eq!(a.clone().bit_be_and(&b), be(|x, y| x & y, 0));
+                assert_eq!(a.clone().bit_be_or(&b), be(|x, y| x | y, 0));
+                assert_eq!(a.clone().bit_be_xor(&b), be(|x, y| x ^ y, 0));
+            }
+
+            let mut zeros = vec![0; len];
+            assert_eq!(zeros.bit_all_zero(), true);
+            assert_eq!(zeros.bit_leading_zeros(), len * 8);
+            for i in 0..len * 8 {
+                zeros.bit_set(i, true);
+                assert_eq!(zeros.bit_leading_zeros(), i);
+                assert_eq!(zeros.bit_trailing_zeros(), len * 8 - 1 - i);
+                assert_eq!(zeros.bit_all_zero(), false);
+                zeros.bit_set(i, false);
+            }
+
+            let a = bytes(len, 3);
+            let not: Vec<u8> = a.iter().map(|&x| !x).collect();
+            assert_eq!(a.clone().bit_not(), not);
+            assert_eq!(vec![0xff; len].bit_all_one(), true);
+
+            // compare the shifts with u128 values
+            if len > 16 {
+                continue;
+            }
+            let low = |k: usize| u128::MAX.checked_shr(128 - k as u32).unwrap_or(0);
+            let (bits, value) = (len * 8, a.iter().fold(0, |acc, &x| (acc << 8) | x as u128));
+            for n in 0..=bits {
+                let (mut shl, mut shr) = (a.clone(), a.clone());
+                assert_eq!(shl.bit_shl(n), value & !low(bits - n) != 0);
+                assert_eq!(shr.bit_shr(n), value & low(n) != 0);
+                let shl_value = value.checked_shl(n as u32).unwrap_or(0) & low(bits);
+                let shr_value = value.checked_shr(n as u32).unwrap_or(0);
+                assert_eq!(shl, shl_value.to_be_bytes()[16 - len..]);
+                assert_eq!(shr, shr_value.to_be_bytes()[16 - len..]);
+            }
+        }
+    }
+
+    fn check_copy<O: BitOrder>() {
+        let src = bytes(20, 0);
+        let data: Vec<u8> = src.iter().map(|b| b.rotate_left(3) ^ 0x5a).collect();
+        for len in [0, 1, 7, 8, 9, 63, 64, 65, 130] {
+            for from in (0..=src.len() * 8 - len).step_by(5) {
+                for dst in (0..=data.len() * 8 - len).step_by(3) {
+                    let mut expected = data.clone();
+                    copy_bits::<O>(&mut expected, dst, &src, from, len);
+                    let mut copy = data.clone();
+                    copy.bit_copy_in::<O>(dst, &src, from, len);
+                    assert_eq!(copy, expected, "copy {from} to {dst} len {len}");
+
+                    let mut expected = data.clone();
+                    copy_bits::<O>(&mut expected, dst, &data, from, len);
+                    let mut within = data.clone();
+                    within.bit_copy_within_in::<O>(from..from + len, dst);
+                    assert_eq!(within, expected, "within {from} to {dst} len {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_copy() {
+        check_copy::<Msb0>();
+        check_copy::<Lsb0>();
+
+        let mut data = [0_u8; 3];
+        data.bit_copy(4, &[0x3f, 0xff, 0xc0], 2, 16);
+        assert_eq!(data, [0x0f, 0xff, 0xf0]);
+        data.bit_copy_within(4..20, 0);
+        assert_eq!(data, [0xff, 0xff, 0xf0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of")]
+    fn test_bit_copy_out() {
+        [0_u8; 2].bit_copy(9, &[0; 2], 0, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of")]
+    fn test_bit_copy_within_out() {
+        [0_u8; 2].bit_copy_within(9..17, 0);
+    }
 }
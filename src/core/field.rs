@@ -0,0 +1,250 @@
+use super::iter::{read_field, write_field};
+use super::{BitOrder, Msb0};
+use crate::{Error, Result};
+
+/**
+ * Integer fields at any bit offset for `[u8]`, the fields may straddle the byte boundaries.
+ * A field of `width` bits at `offset` is the value in the bit order `O`:
+ * `Msb0` puts its highest bit first, `Lsb0` puts its lowest bit first, as `bit_chunks_in` reads.
+ * The fields out of the bits are errors, the buffer is not changed.
+ */
+pub trait BitField {
+    /// Read an unsigned field
+    /// # Parameters
+    /// - `width`: the bits of the field, 0 <= width <= 64
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if `offset + width` is out of the bits
+    /// - `Error::Overflow` if `width` is greater than 64
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitField;
+    /// // 3 bits version, 13 bits flags
+    /// let header = [0b1010_0000, 0b0000_0101];
+    /// assert_eq!(header.bit_read_uint(0, 3), Ok(0b101));
+    /// assert_eq!(header.bit_read_uint(3, 13), Ok(0b0_0000_0000_0101));
+    /// assert!(header.bit_read_uint(8, 9).is_err());
+    /// ```
+    fn bit_read_uint(&self, offset: usize, width: usize) -> Result<u64>;
+
+    /// Read an unsigned field in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitField;
+    /// # use xbits::Lsb0;
+    /// assert_eq!([0b1000_0101, 0b0000_0001].bit_read_uint_in::<Lsb0>(7, 2), Ok(0b11));
+    /// ```
+    fn bit_read_uint_in<O: BitOrder>(&self, offset: usize, width: usize) -> Result<u64>;
+
+    /// Read a signed field, the highest bit of the field is the sign bit
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitField;
+    /// assert_eq!([0b0111_0000].bit_read_int(1, 3), Ok(-1));
+    /// assert_eq!([0b0011_0000].bit_read_int(1, 3), Ok(3));
+    /// ```
+    fn bit_read_int(&self, offset: usize, width: usize) -> Result<i64>;
+
+    /// Read a signed field in the bit order `O`
+    fn bit_read_int_in<O: BitOrder>(&self, offset: usize, width: usize) -> Result<i64>;
+
+    /// Write an unsigned field
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if `offset + width` is out of the bits
+    /// - `Error::Overflow` if `width` is greater than 64 or the value does not fit in `width` bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitField;
+    /// let mut data = [0_u8; 8];
+    /// // 48 bits timestamp after 3 bits
+    /// data.bit_write_uint(3, 48, 0x0123_4567_89ab).unwrap();
+    /// assert_eq!(data.bit_read_uint(3, 48), Ok(0x0123_4567_89ab));
+    /// assert_eq!(data.bit_write_uint(0, 3, 8), Err(xbits::Error::Overflow));
+    /// ```
+    fn bit_write_uint(&mut self, offset: usize, width: usize, value: u64) -> Result<()>;
+
+    /// Write an unsigned field in the bit order `O`
+    fn bit_write_uint_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        width: usize,
+        value: u64,
+    ) -> Result<()>;
+
+    /// Write a signed field in two's complement
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if `offset + width` is out of the bits
+    /// - `Error::Overflow` if `width` is greater than 64 or the value does not fit in `width` bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitField;
+    /// let mut data = [0_u8; 2];
+    /// data.bit_write_int(4, 6, -2).unwrap();
+    /// assert_eq!(data, [0b0000_1111, 0b1000_0000]);
+    /// assert!(data.bit_write_int(4, 6, 32).is_err());
+    /// ```
+    fn bit_write_int(&mut self, offset: usize, width: usize, value: i64) -> Result<()>;
+
+    /// Write a signed field in the bit order `O`
+    fn bit_write_int_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        width: usize,
+        value: i64,
+    ) -> Result<()>;
+}
+
+impl BitField for [u8] {
+    fn bit_read_uint(&self, offset: usize, width: usize) -> Result<u64> {
+        self.bit_read_uint_in::<Msb0>(offset, width)
+    }
+
+    fn bit_read_uint_in<O: BitOrder>(&self, offset: usize, width: usize) -> Result<u64> {
+        check(self, offset, width)?;
+        Ok(read_field::<O>(self, offset, width))
+    }
+
+    fn bit_read_int(&self, offset: usize, width: usize) -> Result<i64> {
+        self.bit_read_int_in::<Msb0>(offset, width)
+    }
+
+    fn bit_read_int_in<O: BitOrder>(&self, offset: usize, width: usize) -> Result<i64> {
+        let value = self.bit_read_uint_in::<O>(offset, width)?;
+        // sign extension by the arithmetic shift
+        let pad = (64 - width) as u32;
+        Ok((value.checked_shl(pad).unwrap_or(0) as i64)
+            .checked_shr(pad)
+            .unwrap_or(0))
+    }
+
+    fn bit_write_uint(&mut self, offset: usize, width: usize, value: u64) -> Result<()> {
+        self.bit_write_uint_in::<Msb0>(offset, width, value)
+    }
+
+    fn bit_write_uint_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        width: usize,
+        value: u64,
+    ) -> Result<()> {
+        check(self, offset, width)?;
+        if value.checked_shr(width as u32).unwrap_or(0) != 0 {
+            return Err(Error::Overflow);
+        }
+        write_field::<O>(self, offset, width, value);
+        Ok(())
+    }
+
+    fn bit_write_int(&mut self, offset: usize, width: usize, value: i64) -> Result<()> {
+        self.bit_write_int_in::<Msb0>(offset, width, value)
+    }
+
+    fn bit_write_int_in<O: BitOrder>(
+        &mut self,
+        offset: usize,
+        width: usize,
+        value: i64,
+    ) -> Result<()> {
+        check(self, offset, width)?;
+        // the bits above the field must be copies of the sign bit
+        let high = value >> width.saturating_sub(1);
+        if width == 0 && value != 0 || width > 0 && high != 0 && high != -1 {
+            return Err(Error::Overflow);
+        }
+        let mask = u64::MAX.checked_shr(64 - width as u32).unwrap_or(0);
+        write_field::<O>(self, offset, width, value as u64 & mask);
+        Ok(())
+    }
+}
+
+/// Check the field is in the bits
+#[inline(always)]
+fn check(data: &[u8], offset: usize, width: usize) -> Result<()> {
+    if width > 64 {
+        return Err(Error::Overflow);
+    }
+    match offset.checked_add(width) {
+        Some(end) if end <= data.len() * 8 => Ok(()),
+        _ => Err(Error::UnexpectedEnd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BitIterator, Bitwise, Lsb0};
+    use crate::test_util::{bytes, read_bits};
+
+    fn check_order<O: BitOrder>(msb: bool) {
+        let data = bytes(24, 0);
+        for width in [0, 1, 3, 13, 48, 57, 63, 64] {
+            let mask = u64::MAX.checked_shr(64 - width as u32).unwrap_or(0);
+            for offset in 0..=data.len() * 8 - width {
+                let value = read_bits::<O>(&data, offset, width, msb);
+                assert_eq!(data.bit_read_uint_in::<O>(offset, width), Ok(value));
+
+                // only the field is changed
+                let mut copy = data.clone();
+                copy.bit_write_uint_in::<O>(offset, width, !value & mask)
+                    .unwrap();
+                let changed: Vec<usize> = (0..data.len() * 8)
+                    .filter(|&i| data.bit_get_in::<O>(i) != copy.bit_get_in::<O>(i))
+                    .collect();
+                assert_eq!(changed, (offset..offset + width).collect::<Vec<_>>());
+            }
+        }
+        let end = data.len() * 8;
+        assert_eq!(
+            data.bit_read_uint_in::<O>(end - 3, 4),
+            Err(Error::UnexpectedEnd)
+        );
+        assert_eq!(
+            data.bit_read_uint_in::<O>(usize::MAX, 1),
+            Err(Error::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn test_uint() {
+        check_order::<Msb0>(true);
+        check_order::<Lsb0>(false);
+
+        let mut data = [0_u8; 4];
+        assert_eq!(data.bit_write_uint(30, 3, 1), Err(Error::UnexpectedEnd));
+        assert_eq!(data.bit_write_uint(0, 3, 0b1000), Err(Error::Overflow));
+        assert_eq!(data, [0; 4]);
+        let wide = [0_u8; 10];
+        assert_eq!(wide.bit_read_uint(0, 65), Err(Error::Overflow));
+        assert_eq!(wide.bit_read_int(0, 65), Err(Error::Overflow));
+        assert_eq!([0_u8; 10].bit_write_uint(0, 65, 0), Err(Error::Overflow));
+        assert_eq!([0_u8; 10].bit_write_int(0, 65, 0), Err(Error::Overflow));
+        data.bit_write_uint(0, 32, 0xdead_beef).unwrap();
+        assert_eq!(data, 0xdead_beef_u32.to_be_bytes());
+        data.bit_write_uint_in::<Lsb0>(0, 32, 0xdead_beef).unwrap();
+        assert_eq!(data, 0xdead_beef_u32.to_le_bytes());
+        assert_eq!(
+            data.bit_iter_in::<Lsb0>().take(4).collect::<Vec<_>>(),
+            [true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_int() {
+        let mut data = [0_u8; 9];
+        for width in [1, 2, 7, 33, 64] {
+            let (min, max) = (i64::MIN >> (64 - width), i64::MAX >> (64 - width));
+            for value in [min, -1, 0, max] {
+                data.bit_write_int(5, width, value).unwrap();
+                assert_eq!(data.bit_read_int(5, width), Ok(value));
+                data.bit_write_int_in::<Lsb0>(5, width, value).unwrap();
+                assert_eq!(data.bit_read_int_in::<Lsb0>(5, width), Ok(value));
+            }
+            if width < 64 {
+                assert_eq!(data.bit_write_int(5, width, min - 1), Err(Error::Overflow));
+                assert_eq!(data.bit_write_int(5, width, max + 1), Err(Error::Overflow));
+            }
+        }
+        assert_eq!(data.bit_write_int(0, 0, 0), Ok(()));
+        assert_eq!(data.bit_write_int(0, 0, -1), Err(Error::Overflow));
+        assert_eq!(data.bit_read_int(0, 0), Ok(0));
+    }
+}
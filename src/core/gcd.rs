@@ -0,0 +1,251 @@
+use super::{BitArith, BitSigned, Bitwise};
+use std::cmp::Ordering;
+
+/**
+ * Number theory helpers implementation for big-endian `[u8]`
+ */
+pub trait BitGcd {
+    type Other: ?Sized;
+
+    /// Greatest common divisor by the binary (Stein's) algorithm
+    /// # Returns
+    /// - `true` if the result is overflowed, only when `self` is zero and `other` does not fit
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitGcd;
+    /// let mut a = 48_u16.to_be_bytes();
+    /// assert_eq!(a.bit_gcd(&[18]), false);
+    /// assert_eq!(a, 6_u16.to_be_bytes());
+    /// let mut a = [0];
+    /// assert_eq!(a.bit_gcd(&[1, 0]), true);
+    /// ```
+    fn bit_gcd(&mut self, other: &Self::Other) -> bool;
+
+    /// Least common multiple
+    /// # Returns
+    /// - `true` if the result is overflowed
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitGcd;
+    /// let mut a = 48_u16.to_be_bytes();
+    /// assert_eq!(a.bit_lcm(&[18]), false);
+    /// assert_eq!(a, 144_u16.to_be_bytes());
+    /// ```
+    fn bit_lcm(&mut self, other: &Self::Other) -> bool;
+
+    /// Extended Euclidean algorithm
+    /// # Returns
+    /// - `(gcd, x, y)` that `self * x + other * y = gcd`
+    /// - `gcd` has the length of the longer operand
+    /// - `x` and `y` are two's-complement signed with one more byte
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitGcd;
+    /// let (gcd, x, y) = [240].bit_ext_gcd(&[46]);
+    /// assert_eq!(gcd, [2]);
+    /// assert_eq!(i16::from_be_bytes(x.try_into().unwrap()), -9);
+    /// assert_eq!(i16::from_be_bytes(y.try_into().unwrap()), 47);
+    /// ```
+    fn bit_ext_gcd(&self, other: &Self::Other) -> (Vec<u8>, Vec<u8>, Vec<u8>);
+
+    /// Modular multiplicative inverse
+    /// # Returns
+    /// - `None` if the value and the modulus are not coprime
+    /// - the inverse in `0..modulus` with the length of the modulus
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitGcd;
+    /// assert_eq!([3].bit_mod_inverse(&[0, 11]), Some(vec![0, 4]));
+    /// assert_eq!([4].bit_mod_inverse(&[0, 12]), None);
+    /// ```
+    fn bit_mod_inverse(&self, modulus: &Self::Other) -> Option<Vec<u8>>;
+}
+
+impl BitGcd for [u8] {
+    type Other = Self;
+
+    fn bit_gcd(&mut self, other: &Self) -> bool {
+        let n = self.len().max(other.len());
+        let (mut a, mut b) = (self.extend_to(n), other.extend_to(n));
+        if a.bit_all_zero() || b.bit_all_zero() {
+            a.bit_be_or(&b);
+        } else {
+            // Remove common factors of two, restored on the result at last
+            let k = a.bit_trailing_zeros().min(b.bit_trailing_zeros());
+            let za = a.bit_trailing_zeros();
+            a.bit_shr(za);
+            loop {
+                let zb = b.bit_trailing_zeros();
+                b.bit_shr(zb);
+                if a.bit_be_cmp(&b) == Ordering::Greater {
+                    std::mem::swap(&mut a, &mut b);
+                }
+                b.bit_be_sub(&a);
+                if b.bit_all_zero() {
+                    break;
+                }
+            }
+            a.bit_shl(k);
+        }
+        let (high, low) = a.split_at(n - self.len());
+        self.copy_from_slice(low);
+        !high.bit_all_zero()
+    }
+
+    fn bit_lcm(&mut self, other: &Self) -> bool {
+        if self.bit_all_zero() || other.bit_all_zero() {
+            self.fill(0);
+            return false;
+        }
+        let mut gcd = self.to_vec();
+        gcd.bit_gcd(other);
+        self.bit_be_div(&gcd);
+        self.bit_be_mul(other)
+    }
+
+    fn bit_ext_gcd(&self, other: &Self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let n = self.len().max(other.len());
+        let signed = |v: &[u8]| v.extend_to(n + 1);
+        let (mut old_r, mut r) = (self.extend_to(n), other.extend_to(n));
+        let (mut old_s, mut s) = (signed(&[1]), signed(&[0]));
+        let (mut old_t, mut t) = (signed(&[0]), signed(&[1]));
+
+        // The coefficients are bounded by the operands, so never overflow `n + 1` bytes
+        while !r.bit_all_zero() {
+            let mut q = old_r.clone();
+            q.bit_be_div(&r);
+            let q = signed(&q);
+
+            old_r.bit_be_rem(&r);
+            std::mem::swap(&mut old_r, &mut r);
+            for (old, new) in [(&mut old_s, &mut s), (&mut old_t, &mut t)] {
+                let mut qx = new.clone();
+                qx.bit_be_signed_mul(&q);
+                old.bit_be_signed_sub(&qx);
+                std::mem::swap(old, new);
+            }
+        }
+        (old_r, old_s, old_t)
+    }
+
+    fn bit_mod_inverse(&self, modulus: &Self) -> Option<Vec<u8>> {
+        if modulus.bit_all_zero() {
+            return None;
+        }
+        let mut value = self.to_vec();
+        value.bit_be_rem(modulus);
+        let (gcd, mut x, _) = value.bit_ext_gcd(modulus);
+        if gcd.bit_be_cmp(&[1]) != Ordering::Equal {
+            return None;
+        }
+        if x.bit_is_negative() {
+            let modulus = modulus.extend_to(x.len());
+            x.bit_be_signed_add(&modulus);
+        }
+        Some(x[x.len() - modulus.len()..].to_vec())
+    }
+}
+
+trait GcdExtend {
+    /// Zero extend or truncate leading bytes to `n` bytes
+    fn extend_to(&self, n: usize) -> Vec<u8>;
+}
+
+impl GcdExtend for [u8] {
+    fn extend_to(&self, n: usize) -> Vec<u8> {
+        let mut data = vec![0; n];
+        let m = self.len().min(n);
+        data[n - m..].copy_from_slice(&self[self.len() - m..]);
+        data
+    }
+}
+
+#[cfg(test)]
+mod test_gcd {
+    use super::*;
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+
+    const VALUES: &[u64] = &[
+        0,
+        1,
+        2,
+        6,
+        12,
+        17,
+        18,
+        240,
+        1024,
+        65537,
+        1 << 40,
+        u64::MAX - 58,
+    ];
+
+    #[test]
+    fn test_gcd_lcm() {
+        for &a in VALUES {
+            for &b in VALUES {
+                let mut x = a.to_be_bytes();
+                assert!(!x.bit_gcd(&b.to_be_bytes()));
+                assert_eq!(x, gcd(a, b).to_be_bytes());
+
+                // the gcd of zero does not fit in the shorter buffer
+                let mut x = (a as u8).to_be_bytes();
+                let fit = a as u8 != 0 || b <= u8::MAX as u64;
+                assert_eq!(x.bit_gcd(&b.to_be_bytes()), !fit);
+                if fit {
+                    assert_eq!(x, (gcd(a as u8 as u64, b) as u8).to_be_bytes());
+                }
+
+                let mut x = a.to_be_bytes();
+                let lcm = match gcd(a, b) {
+                    0 => Some(0),
+                    g => (a / g).checked_mul(b),
+                };
+                assert_eq!(x.bit_lcm(&b.to_be_bytes()), lcm.is_none());
+                if let Some(lcm) = lcm {
+                    assert_eq!(x, lcm.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ext_gcd() {
+        for &a in VALUES {
+            for &b in VALUES {
+                let (g, x, y) = a.to_be_bytes().bit_ext_gcd(&b.to_be_bytes());
+                assert_eq!(g, gcd(a, b).to_be_bytes());
+
+                let x = x.bit_sign_extend(16);
+                let y = y.bit_sign_extend(16);
+                let x = i128::from_be_bytes(x.try_into().unwrap());
+                let y = i128::from_be_bytes(y.try_into().unwrap());
+                let bezout = (a as i128)
+                    .wrapping_mul(x)
+                    .wrapping_add((b as i128).wrapping_mul(y));
+                assert_eq!(bezout, gcd(a, b) as i128, "{a} * {x} + {b} * {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        for &a in VALUES {
+            for &m in VALUES {
+                let inv = a.to_be_bytes().bit_mod_inverse(&m.to_be_bytes());
+                match (m, gcd(a % m.max(1), m)) {
+                    (0, _) => assert_eq!(inv, None),
+                    (_, 1) => {
+                        let inv = u64::from_be_bytes(inv.unwrap().try_into().unwrap());
+                        assert!(inv < m.max(2));
+                        assert_eq!((a as u128 * inv as u128) % m as u128, 1 % m as u128);
+                    }
+                    _ => assert_eq!(inv, None),
+                }
+            }
+        }
+    }
+}
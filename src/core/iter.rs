@@ -1,3 +1,4 @@
+use super::{BitOrder, Msb0};
 use crate::assert_range;
 
 /**
@@ -20,7 +21,7 @@ pub trait BitIterator {
     /// # Parameters  
     /// - `T`: the type to contains the grouped bits  
     /// - `n`: the number of bits to group  
-    /// - 1 <= n <= T::BITS <= 32
+    /// - 1 <= n <= T::BITS <= 64
     ///   
     /// # Examples  
     /// ```
@@ -37,36 +38,71 @@ pub trait BitIterator {
     fn bit_chunks<T>(&self, n: usize) -> impl Iterator<Item = T>
     where
         T: TryFrom<u64> + Default;
+
+    /// Iterator bits in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitIterator;
+    /// # use xbits::Lsb0;
+    /// assert_eq!(
+    ///   [0b1111_0000_u8].bit_iter_in::<Lsb0>().collect::<Vec<bool>>(),
+    ///   vec![false, false, false, false, true, true, true, true]
+    /// );
+    /// ```
+    fn bit_iter_in<O: BitOrder>(&self) -> impl DoubleEndedIterator<Item = bool>;
+
+    /// Returns the bits in the buffer grouped by n in the bit order `O`
+    /// The first bit of a group is the most significant bit of the value in `Msb0`,
+    /// and the least significant bit of the value in `Lsb0`.
+    ///
+    /// # Parameters
+    /// - `T`: the type to contains the grouped bits
+    /// - `n`: the number of bits to group
+    /// - 1 <= n <= T::BITS <= 64
+    ///
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitIterator;
+    /// # use xbits::Lsb0;
+    /// assert_eq!(
+    ///     vec![0b1010_0011, 0b0000_0001].bit_chunks_in::<Lsb0, u8>(6).collect::<Vec<u8>>(),
+    ///     vec![0b10_0011, 0b00_0110, 0b00_0000]
+    /// );
+    /// ```
+    fn bit_chunks_in<O: BitOrder, T>(&self, n: usize) -> impl Iterator<Item = T>
+    where
+        T: TryFrom<u64> + Default;
 }
 
 impl BitIterator for [u8] {
     fn bit_iter(&self) -> impl DoubleEndedIterator<Item = bool> {
-        self.iter()
-            .flat_map(|&v| (0_u8..8).rev().map(move |n| (v & (1 << n)) != 0))
+        self.bit_iter_in::<Msb0>()
     }
 
     fn bit_chunks<T>(&self, n: usize) -> impl Iterator<Item = T>
     where
         T: TryFrom<u64> + Default,
     {
-        let valid_size = (std::mem::size_of::<T>() * 8).min(32);
+        self.bit_chunks_in::<Msb0, T>(n)
+    }
+
+    fn bit_iter_in<O: BitOrder>(&self) -> impl DoubleEndedIterator<Item = bool> {
+        self.iter()
+            .flat_map(|&v| (0..8).map(move |n| (v & O::mask(n)) != 0))
+    }
+
+    fn bit_chunks_in<O: BitOrder, T>(&self, n: usize) -> impl Iterator<Item = T>
+    where
+        T: TryFrom<u64> + Default,
+    {
+        let valid_size = (std::mem::size_of::<T>() * 8).min(64);
         assert_range!(n, 1, valid_size, "bit_chunks");
 
-        // enumerate bytes window of 64 bits width, split item values from those windows
-        let bit_mask: u64 = (0..n).fold(0, |acc, v| acc | (1 << v));
-        let mut bit_pos = 0;
-        (0..self.len()).flat_map(move |i| {
-            let window_value = self.byte_window_64(i);
-            let window_end = i * 8 + u64::BITS as usize; // current bit window end
-            debug_assert!(matches!(window_end - bit_pos, 1..=64)); // current bit window size
-
-            let mut vs = vec![];
-            while (bit_pos + n) <= window_end && (bit_pos + n) < self.len() * 8 + n {
-                bit_pos += n;
-                let value = (window_value >> (window_end - bit_pos)) & bit_mask;
-                vs.push(value.try_into().unwrap_or_default());
-            }
-            vs
+        // read the item values as the fields of `n` bits, the last one is tail padding zero bits
+        let count = (self.len() * 8).div_ceil(n);
+        (0..count).map(move |k| {
+            let value = read_field::<O>(self, k * n, n);
+            value.try_into().unwrap_or_default()
         })
     }
 }
@@ -107,98 +143,140 @@ pub trait FromBits {
     where
         T: TryInto<u64>,
         U: Iterator<Item = T>;
+
+    /// Convert enumerated bool values to buffer in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::{FromBits, Lsb0};
+    /// let bits = vec![false, false, false, false, true, true, true, true, true];
+    /// let data = Vec::from_bits_in::<Lsb0, _>(bits.into_iter());
+    /// assert_eq!(data, [0b1111_0000, 0b0000_0001]);
+    /// ```
+    fn from_bits_in<O, U>(bits: U) -> Self
+    where
+        O: BitOrder,
+        U: Iterator<Item = bool>;
+
+    /// Conjoin the lowest n bits of each value in the bit order `O`, the reverse of `bit_chunks_in`
+    ///
+    /// # Parameters
+    /// - `n`: the number of bits to conjoin
+    /// - 1 <= n <= 32
+    ///
+    /// # Examples
+    /// ```
+    /// # use xbits::{FromBits, Lsb0};
+    /// assert_eq!(
+    ///     Vec::from_bit_chunks_in::<Lsb0, _, _>([0b10_0011_u8, 0b00_0110, 0b00_0000].into_iter(), 6),
+    ///     vec![0b1010_0011, 0b0000_0001, 0b0000_0000]
+    /// );
+    /// ```
+    fn from_bit_chunks_in<O, T, U>(chunks: U, n: usize) -> Self
+    where
+        O: BitOrder,
+        T: TryInto<u64>,
+        U: Iterator<Item = T>;
 }
 
 impl FromBits for Vec<u8> {
     fn from_bits<U>(bits: U) -> Self
     where
         U: Iterator<Item = bool>,
+    {
+        Self::from_bits_in::<Msb0, U>(bits)
+    }
+
+    fn from_bit_chunks<T, U>(chunks: U, n: usize) -> Self
+    where
+        T: TryInto<u64>,
+        U: Iterator<Item = T>,
+    {
+        Self::from_bit_chunks_in::<Msb0, T, U>(chunks, n)
+    }
+
+    fn from_bits_in<O, U>(bits: U) -> Self
+    where
+        O: BitOrder,
+        U: Iterator<Item = bool>,
     {
         let mut v = 0_u8;
         bits.chain([false; 7])
             .enumerate()
             .filter_map(|(i, bit)| {
-                let n = 7 - (i % 8);
                 if bit {
-                    v |= 1 << n;
+                    v |= O::mask(i % 8);
                 }
-                match n {
-                    0 => Some(std::mem::take(&mut v)),
+                match i % 8 {
+                    7 => Some(std::mem::take(&mut v)),
                     _ => None,
                 }
             })
             .collect()
     }
 
-    fn from_bit_chunks<T, U>(chunks: U, n: usize) -> Self
+    fn from_bit_chunks_in<O, T, U>(chunks: U, n: usize) -> Self
     where
+        O: BitOrder,
         T: TryInto<u64>,
         U: Iterator<Item = T>,
     {
         assert_range!(n, 1, 32, "from_chunks");
-        let bit_mask: u64 = (0..n).fold(0, |acc, v| acc | (1 << v));
 
-        let mut rem = TinyBits::new(0, 0);
+        // cache the pending bits, split whole bytes from the cache
+        let (mut cache, mut len) = (0_u128, 0);
         let mut vs: Vec<u8> = chunks
             .map(|v| v.try_into().unwrap_or_default())
-            .flat_map(|mut value: u64| {
-                value &= bit_mask;
-                value <<= u64::BITS as usize - n;
-                rem.prefix_to(&mut value);
-
-                let partial = (n + rem.len()) / 8;
-                let bytes = value.to_be_bytes();
-                rem = TinyBits::new(bytes[partial], (n + rem.len()) % 8);
-                bytes[..partial].to_vec()
+            .flat_map(|value: u64| {
+                cache = O::push(cache, len, value, n);
+                len += n;
+                (0..len / 8)
+                    .map(|_| {
+                        let (byte, rest) = O::pop(cache, len, 8);
+                        (cache, len) = (rest, len - 8);
+                        byte as u8
+                    })
+                    .collect::<Vec<u8>>()
             })
             .collect();
-        vs.extend_from_slice(&rem.value());
+        if len > 0 {
+            // tail padding zero bits
+            let (byte, _) = O::pop(O::push(cache, len, 0, 8 - len), 8, 8);
+            vs.push(byte as u8);
+        }
         vs
     }
 }
 
-trait ByteWindow {
-    // get window value from bytes. If insufficient, tail padding zero
-    fn byte_window_64(&self, byte_index: usize) -> u64;
+/// Read the `n` bits value at the bit `start` in the bit order `O`, 0 <= n <= 64.
+/// The bits out of the data are zeros.
+pub(crate) fn read_field<O: BitOrder>(data: &[u8], start: usize, n: usize) -> u64 {
+    let (bytes, _, _) = field_window(data, start);
+    let shift = O::field_shift(start % 8, n);
+    let low_mask = u64::MAX.checked_shr(64 - n as u32).unwrap_or(0) as u128;
+    (O::load_128(bytes).checked_shr(shift).unwrap_or(0) & low_mask) as u64
 }
 
-impl ByteWindow for [u8] {
-    fn byte_window_64(&self, i: usize) -> u64 {
-        let bytes = self[i..]
-            .iter()
-            .copied()
-            .chain(std::iter::repeat(0))
-            .take(8)
-            .collect::<Vec<u8>>();
-        u64::from_be_bytes(bytes.try_into().unwrap())
-    }
+/// Write the lowest `n` bits of the value at the bit `start` in the bit order `O`, 0 <= n <= 64.
+/// The bits out of the data are dropped.
+pub(crate) fn write_field<O: BitOrder>(data: &mut [u8], start: usize, n: usize, value: u64) {
+    let (bytes, byte, len) = field_window(data, start);
+    let shift = O::field_shift(start % 8, n);
+    let low_mask = u64::MAX.checked_shr(64 - n as u32).unwrap_or(0) as u128;
+    let mask = low_mask.checked_shl(shift).unwrap_or(0);
+    let window =
+        O::load_128(bytes) & !mask | ((value as u128 & low_mask).checked_shl(shift)).unwrap_or(0);
+    data[byte..byte + len].copy_from_slice(&O::store_128(window)[..len]);
 }
 
-/// 0~7 bits data
-#[derive(Debug)]
-struct TinyBits {
-    data: u8,
-    len: usize,
-}
-
-impl TinyBits {
-    pub fn new(data: u8, len: usize) -> Self {
-        TinyBits { data, len }
-    }
-    pub fn len(&self) -> usize {
-        self.len
-    }
-    pub fn value(&self) -> Vec<u8> {
-        match self.len > 0 {
-            true => vec![self.data],
-            false => vec![],
-        }
-    }
-    /// insert bits as value prefix
-    pub fn prefix_to(&self, value: &mut u64) {
-        if self.len > 0 {
-            *value >>= self.len;
-            *value |= (self.data as u64) << (64 - 8); // u64 left byte
-        }
-    }
+/// The 16 bytes from the byte of the bit `start`, tail padding zero,
+/// with the index and the number of the bytes in the data.
+/// A field of 64 bits at the bit offset 7 takes 9 bytes, so one window holds any field,
+/// it is the only window of the fields and the chunks.
+#[inline(always)]
+fn field_window(data: &[u8], start: usize) -> ([u8; 16], usize, usize) {
+    let byte = (start / 8).min(data.len());
+    let len = (data.len() - byte).min(16);
+    let mut bytes = [0; 16];
+    bytes[..len].copy_from_slice(&data[byte..byte + len]);
+    (bytes, byte, len)
 }
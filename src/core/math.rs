@@ -0,0 +1,273 @@
+use super::BitArith;
+use super::Bitwise;
+use crate::assert_range;
+use std::cmp::Ordering;
+
+/**
+ * Integer roots, logarithms and powers implementation for big-endian `[u8]`
+ */
+pub trait BitMath {
+    type Other: ?Sized;
+
+    /// Integer square root, rounded down
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!([0b0000_0001, 0b0000_0000].bit_isqrt(), [0, 16]);
+    /// assert_eq!([0b0000_0000, 0b0000_1111].bit_isqrt(), [0, 3]);
+    /// ```
+    fn bit_isqrt(&mut self) -> &mut Self;
+
+    /// Integer cube root, rounded down
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!(1000_u16.to_be_bytes().bit_icbrt(), 10_u16.to_be_bytes());
+    /// assert_eq!(999_u16.to_be_bytes().bit_icbrt(), 9_u16.to_be_bytes());
+    /// ```
+    fn bit_icbrt(&mut self) -> &mut Self;
+
+    /// Integer `n`-th root, rounded down
+    /// # Parameters
+    /// - `n`: the degree of the root, `n >= 1`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!(u64::MAX.to_be_bytes().bit_nth_root(4), 65535_u64.to_be_bytes());
+    /// assert_eq!(u64::MAX.to_be_bytes().bit_nth_root(64), 1_u64.to_be_bytes());
+    /// ```
+    fn bit_nth_root(&mut self, n: u32) -> &mut Self;
+
+    /// Base 2 logarithm, rounded down
+    /// # Returns
+    /// - `None` if the value is zero
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!([0b0000_0001, 0b0000_0000].bit_ilog2(), Some(8));
+    /// assert_eq!([0b0000_0000, 0b0000_0000].bit_ilog2(), None);
+    /// ```
+    fn bit_ilog2(&self) -> Option<usize>;
+
+    /// Base 10 logarithm, rounded down
+    /// # Returns
+    /// - `None` if the value is zero
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!(9999_u16.to_be_bytes().bit_ilog10(), Some(3));
+    /// assert_eq!(10000_u16.to_be_bytes().bit_ilog10(), Some(4));
+    /// ```
+    fn bit_ilog10(&self) -> Option<usize>;
+
+    /// Logarithm of the given base, rounded down
+    /// # Returns
+    /// - `None` if the value is zero or the base is less than 2
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!(242_u16.to_be_bytes().bit_ilog(&[3]), Some(4));
+    /// assert_eq!(243_u16.to_be_bytes().bit_ilog(&[3]), Some(5));
+    /// assert_eq!(243_u16.to_be_bytes().bit_ilog(&[1]), None);
+    /// ```
+    fn bit_ilog(&self, base: &Self::Other) -> Option<usize>;
+
+    /// Raise to the power of `exp` in place
+    /// # Returns
+    /// - `true` if the result is overflowed
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// // bits needed for the entropy of 50 dice rolls
+    /// let mut rolls = [0; 32];
+    /// rolls[31] = 6;
+    /// assert_eq!(rolls.bit_pow(50), false);
+    /// assert_eq!(rolls.bit_ilog2().map(|n| n + 1), Some(130));
+    /// ```
+    fn bit_pow(&mut self, exp: u32) -> bool;
+
+    /// Check if exactly one bit is set
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// assert_eq!([0b0000_0000, 0b0001_0000].bit_is_power_of_two(), true);
+    /// assert_eq!([0b0000_0001, 0b0001_0000].bit_is_power_of_two(), false);
+    /// assert_eq!([0b0000_0000, 0b0000_0000].bit_is_power_of_two(), false);
+    /// ```
+    fn bit_is_power_of_two(&self) -> bool;
+
+    /// Round up to the smallest power of two greater than or equal to the value
+    /// # Returns
+    /// - `true` if the result is overflowed, the value is set to zero
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitMath;
+    /// let mut a = [0b0000_0001, 0b0001_0000];
+    /// assert_eq!(a.bit_next_power_of_two(), false);
+    /// assert_eq!(a, [0b0000_0010, 0b0000_0000]);
+    /// ```
+    fn bit_next_power_of_two(&mut self) -> bool;
+}
+
+impl BitMath for [u8] {
+    type Other = Self;
+
+    fn bit_isqrt(&mut self) -> &mut Self {
+        self.bit_nth_root(2)
+    }
+
+    fn bit_icbrt(&mut self) -> &mut Self {
+        self.bit_nth_root(3)
+    }
+
+    fn bit_nth_root(&mut self, n: u32) -> &mut Self {
+        assert_range!(n, 1, u32::MAX, "bit_nth_root");
+        let value = self.to_vec();
+        self.fill(0);
+
+        // Set the root bits from high to low while its power does not exceed the value
+        let Some(log2) = value.bit_ilog2() else {
+            return self;
+        };
+        let bits = self.len() * 8;
+        for i in (0..=log2 / n as usize).rev() {
+            self.bit_set(bits - 1 - i, true);
+            let mut power = self.to_vec();
+            if power.bit_pow(n) || power.bit_be_cmp(&value) == Ordering::Greater {
+                self.bit_set(bits - 1 - i, false);
+            }
+        }
+        self
+    }
+
+    fn bit_ilog2(&self) -> Option<usize> {
+        match self.bit_leading_zeros() {
+            n if n == self.len() * 8 => None,
+            n => Some(self.len() * 8 - 1 - n),
+        }
+    }
+
+    fn bit_ilog10(&self) -> Option<usize> {
+        self.bit_ilog(&[10])
+    }
+
+    fn bit_ilog(&self, base: &Self) -> Option<usize> {
+        if self.bit_all_zero() || base.bit_be_cmp(&[2]) == Ordering::Less {
+            return None;
+        }
+        let mut value = self.to_vec();
+        let mut log = 0;
+        while value.bit_be_cmp(base) != Ordering::Less {
+            value.bit_be_div(base);
+            log += 1;
+        }
+        Some(log)
+    }
+
+    fn bit_pow(&mut self, exp: u32) -> bool {
+        let mut base = self.to_vec();
+        let mut base_overflow = false;
+        let mut overflow = false;
+
+        // Square and multiply, the base overflow only matters if it is used later
+        self.fill(0);
+        self.bit_be_add(&[1]);
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                overflow |= self.bit_be_mul(&base) || base_overflow;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                let square = base.clone();
+                base_overflow |= base.bit_be_mul(&square);
+            }
+        }
+        overflow
+    }
+
+    fn bit_is_power_of_two(&self) -> bool {
+        self.bit_leading_zeros() + self.bit_trailing_zeros() + 1 == self.len() * 8
+    }
+
+    fn bit_next_power_of_two(&mut self) -> bool {
+        if self.bit_is_power_of_two() {
+            return false;
+        }
+        let bits = self.len() * 8;
+        let n = self.bit_ilog2().map_or(0, |n| n + 1);
+        self.fill(0);
+        if n >= bits {
+            return true;
+        }
+        self.bit_set(bits - 1 - n, true);
+        false
+    }
+}
+
+#[cfg(test)]
+mod test_math {
+    use super::*;
+
+    const VALUES: &[u64] = &[
+        0,
+        1,
+        2,
+        3,
+        4,
+        7,
+        8,
+        9,
+        26,
+        27,
+        255,
+        256,
+        1000,
+        65535,
+        u64::MAX / 7,
+        u64::MAX,
+    ];
+
+    #[test]
+    fn test_roots() {
+        for &v in VALUES {
+            let mut x = v.to_be_bytes();
+            assert_eq!(x.bit_isqrt(), v.isqrt().to_be_bytes(), "isqrt {v}");
+
+            let mut x = v.to_be_bytes();
+            x.bit_icbrt();
+            let root = u64::from_be_bytes(x);
+            assert!(root.pow(3) <= v && (root + 1).checked_pow(3).is_none_or(|p| p > v));
+
+            let mut x = v.to_be_bytes();
+            assert_eq!(x.bit_nth_root(1), v.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_logs() {
+        for &v in VALUES {
+            let x = v.to_be_bytes();
+            assert_eq!(x.bit_ilog2(), v.checked_ilog2().map(|n| n as usize));
+            assert_eq!(x.bit_ilog10(), v.checked_ilog10().map(|n| n as usize));
+            assert_eq!(x.bit_ilog(&[7]), v.checked_ilog(7).map(|n| n as usize));
+            assert_eq!(x.bit_is_power_of_two(), v.is_power_of_two());
+
+            let mut x = v.to_be_bytes();
+            let next = v.checked_next_power_of_two();
+            assert_eq!(x.bit_next_power_of_two(), next.is_none());
+            assert_eq!(x, next.unwrap_or(0).to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_pow() {
+        for &v in VALUES {
+            for exp in [0, 1, 2, 3, 5, 8, 63, 64] {
+                let mut x = v.to_be_bytes();
+                assert_eq!(x.bit_pow(exp), v.checked_pow(exp).is_none(), "{v} ^ {exp}");
+                assert_eq!(x, v.wrapping_pow(exp).to_be_bytes(), "{v} ^ {exp}");
+            }
+        }
+    }
+}
@@ -1,9 +1,34 @@
 mod arith;
+mod bits;
 mod bitwise;
 mod convert;
+mod field;
+mod gcd;
 mod iter;
+mod math;
+mod order;
+mod prime;
+mod range;
+mod search;
+mod set;
+mod shift;
+mod signed;
 
 pub use arith::BitArith;
+pub use bits::Bits;
 pub use bitwise::Bitwise;
 pub use convert::ToBits;
+pub use field::BitField;
+pub use gcd::BitGcd;
 pub use iter::{BitIterator, FromBits};
+pub(crate) use iter::{read_field, write_field};
+pub use math::BitMath;
+pub use order::{BitOrder, Lsb0, Msb0};
+pub use prime::{BitPrime, random_prime, random_prime_with};
+pub use range::BitRange;
+pub(crate) use range::RangeExtend;
+pub(crate) use search::indexed_word;
+pub use search::{BitSearch, Positions, Runs};
+pub use set::{Align, BitSet};
+pub use shift::BitShift;
+pub use signed::BitSigned;
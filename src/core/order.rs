@@ -0,0 +1,189 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/**
+ * Bit order in each byte, selected at type level.
+ * The order decides which bit of a byte is indexed first by `bit_get_in`, `bit_iter_in`,
+ * `bit_chunks_in`, the `BitsRef`/`BitsMut` views and the bit streams.
+ */
+pub trait BitOrder:
+    Debug + Clone + Copy + Default + PartialEq + Eq + Hash + Send + Sync + 'static + sealed::Order
+{
+}
+
+/// Most significant bit first, the index 0 is `0b1000_0000`. The default order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Msb0;
+
+/// Least significant bit first, the index 0 is `0b0000_0001`.
+/// Used by hardware registers, DEFLATE and CAN frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Lsb0;
+
+impl BitOrder for Msb0 {}
+impl BitOrder for Lsb0 {}
+
+mod sealed {
+    /// Bit layout primitives of an order, not implemented outside of the crate
+    pub trait Order {
+        /// Mask of the bit at `index` of a byte, 0 <= index < 8
+        fn mask(index: usize) -> u8;
+
+        /// Mask of the bits at `start..end` of a byte, 0 <= start <= end <= 8
+        fn range_mask(start: usize, end: usize) -> u8;
+
+        /// Load a window of 16 bytes, the first bit of the bytes is at offset 0
+        fn load_128(bytes: [u8; 16]) -> u128;
+
+        /// Store a window of 16 bytes, the reverse of `load_128`
+        fn store_128(window: u128) -> [u8; 16];
+
+        /// Shift of the lowest bit of the `n` bits value at `offset` of a 128 bits window, offset + n <= 128
+        fn field_shift(offset: usize, n: usize) -> u32;
+
+        /// Load a word of 8 bytes, the bit at index `i` of the bytes is at bit `i` of the word
+        fn load_indexed(bytes: [u8; 8]) -> u64;
+
+        /// Append the `n` bits value to a cache of `len` bits, len + n <= 128
+        fn push(cache: u128, len: usize, value: u64, n: usize) -> u128;
+
+        /// Split the first `n` bits from a cache of `len` bits, n <= 64
+        /// # Returns
+        /// - the value of the `n` bits and the cache of the rest bits
+        fn pop(cache: u128, len: usize, n: usize) -> (u64, u128);
+    }
+}
+
+/// Mask of the lowest `n` bits, 0 <= n <= 128
+#[inline(always)]
+fn low_mask(n: usize) -> u128 {
+    u128::MAX
+        .checked_shr((u128::BITS as usize - n) as u32)
+        .unwrap_or(0)
+}
+
+impl sealed::Order for Msb0 {
+    #[inline(always)]
+    fn mask(index: usize) -> u8 {
+        0b1000_0000 >> index
+    }
+
+    #[inline(always)]
+    fn range_mask(start: usize, end: usize) -> u8 {
+        ((0xff_u16 >> start) & !(0xff_u16 >> end)) as u8
+    }
+
+    #[inline(always)]
+    fn load_128(bytes: [u8; 16]) -> u128 {
+        u128::from_be_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn store_128(window: u128) -> [u8; 16] {
+        window.to_be_bytes()
+    }
+
+    #[inline(always)]
+    fn field_shift(offset: usize, n: usize) -> u32 {
+        (u128::BITS as usize - offset - n) as u32
+    }
+
+    #[inline(always)]
+    fn load_indexed(bytes: [u8; 8]) -> u64 {
+        u64::from_be_bytes(bytes).reverse_bits()
+    }
+
+    #[inline(always)]
+    fn push(cache: u128, _len: usize, value: u64, n: usize) -> u128 {
+        cache.checked_shl(n as u32).unwrap_or(0) | (value as u128 & low_mask(n))
+    }
+
+    #[inline(always)]
+    fn pop(cache: u128, len: usize, n: usize) -> (u64, u128) {
+        let rest = len - n;
+        let value = cache.checked_shr(rest as u32).unwrap_or(0) & low_mask(n);
+        (value as u64, cache & low_mask(rest))
+    }
+}
+
+impl sealed::Order for Lsb0 {
+    #[inline(always)]
+    fn mask(index: usize) -> u8 {
+        0b0000_0001 << index
+    }
+
+    #[inline(always)]
+    fn range_mask(start: usize, end: usize) -> u8 {
+        ((0xff_u16 << start) & !(0xff_u16 << end)) as u8
+    }
+
+    #[inline(always)]
+    fn load_128(bytes: [u8; 16]) -> u128 {
+        u128::from_le_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn store_128(window: u128) -> [u8; 16] {
+        window.to_le_bytes()
+    }
+
+    #[inline(always)]
+    fn field_shift(offset: usize, _n: usize) -> u32 {
+        offset as u32
+    }
+
+    #[inline(always)]
+    fn load_indexed(bytes: [u8; 8]) -> u64 {
+        u64::from_le_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn push(cache: u128, len: usize, value: u64, n: usize) -> u128 {
+        cache
+            | (value as u128 & low_mask(n))
+                .checked_shl(len as u32)
+                .unwrap_or(0)
+    }
+
+    #[inline(always)]
+    fn pop(cache: u128, _len: usize, n: usize) -> (u64, u128) {
+        let value = cache & low_mask(n);
+        (value as u64, cache.checked_shr(n as u32).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod test_order {
+    use super::sealed::Order;
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(
+            (0..8).map(Msb0::mask).collect::<Vec<_>>(),
+            [128, 64, 32, 16, 8, 4, 2, 1]
+        );
+        assert_eq!(
+            (0..8).map(Lsb0::mask).collect::<Vec<_>>(),
+            [1, 2, 4, 8, 16, 32, 64, 128]
+        );
+        assert_eq!(Msb0::range_mask(1, 4), 0b0111_0000);
+        assert_eq!(Lsb0::range_mask(1, 4), 0b0000_1110);
+        assert_eq!(Msb0::range_mask(0, 8), 0xff);
+        assert_eq!(Lsb0::range_mask(0, 8), 0xff);
+        assert_eq!(Lsb0::range_mask(3, 3), 0);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let cache = Msb0::push(0, 0, 0b101, 3);
+        let cache = Msb0::push(cache, 3, u64::MAX, 64);
+        assert_eq!(Msb0::pop(cache, 67, 3), (0b101, u64::MAX as u128));
+        assert_eq!(Msb0::pop(cache, 67, 0), (0, cache));
+
+        let cache = Lsb0::push(0, 0, 0b101, 3);
+        let cache = Lsb0::push(cache, 3, u64::MAX, 64);
+        assert_eq!(Lsb0::pop(cache, 67, 3), (0b101, u64::MAX as u128));
+        assert_eq!(Lsb0::pop(cache, 67, 0), (0, cache));
+    }
+}
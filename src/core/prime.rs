@@ -0,0 +1,390 @@
+use super::{BitArith, BitIterator, Bitwise};
+use crate::assert_range;
+use std::cmp::Ordering;
+
+/// Rounds of Miller-Rabin test used by `bit_next_prime` and `random_prime`
+const PRIME_ROUNDS: usize = 32;
+
+/// Deterministic Miller-Rabin bases for all values below 2^64
+const BASES_64: [u8; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Small primes for trial division before Miller-Rabin test
+const SMALL_PRIMES: [u32; 24] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+];
+
+/**
+ * Primality testing implementation for big-endian `[u8]`
+ */
+pub trait BitPrime {
+    type Other: ?Sized;
+
+    /// Modular exponentiation `self = self ^ exp % modulus`
+    /// # Returns
+    /// - `true` if the modulus is zero, `self` is unchanged
+    /// - `true` if the result is overflowed, `self` is shorter than the modulus and the result does not fit,
+    ///   `self` keeps the low bytes of the result
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitPrime;
+    /// let mut a = 4_u16.to_be_bytes();
+    /// assert_eq!(a.bit_mod_pow(&[13], &[1, 241]), false);
+    /// assert_eq!(a, 445_u16.to_be_bytes());
+    /// assert_eq!([200].bit_mod_pow(&[2], &[1, 0, 0]), true);
+    /// ```
+    fn bit_mod_pow(&mut self, exp: &Self::Other, modulus: &Self::Other) -> bool;
+
+    /// Miller-Rabin probable prime test
+    /// # Parameters
+    /// - `rounds`: the number of random bases, ignored for values below 2^64
+    ///   which are tested with a deterministic base set
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitPrime;
+    /// assert_eq!(((1_u128 << 61) - 1).to_be_bytes().bit_is_probable_prime(0), true);
+    /// assert_eq!(((1_u128 << 89) - 1).to_be_bytes().bit_is_probable_prime(16), true);
+    /// assert_eq!(561_u16.to_be_bytes().bit_is_probable_prime(16), false);
+    /// ```
+    fn bit_is_probable_prime(&self, rounds: usize) -> bool;
+
+    /// Set to the smallest probable prime greater than the value
+    /// # Returns
+    /// - `true` if no prime fits in the length, `self` is unchanged
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitPrime;
+    /// let mut a = [1, 0, 0, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(a.bit_next_prime(), false);
+    /// assert_eq!(a, [1, 0, 0, 0, 0, 0, 0, 0, 13]);
+    /// assert_eq!([0xff].bit_next_prime(), true);
+    /// assert_eq!([].bit_next_prime(), true);
+    /// ```
+    fn bit_next_prime(&mut self) -> bool;
+}
+
+impl BitPrime for [u8] {
+    type Other = Self;
+
+    fn bit_mod_pow(&mut self, exp: &Self, modulus: &Self) -> bool {
+        if modulus.bit_all_zero() {
+            return true; // Division by zero, return overflow
+        }
+        let mut base = self.mod_reduce(modulus);
+        let mut result = vec![0; base.len()];
+        result.bit_be_add(&[1]);
+        result.bit_be_rem(modulus);
+
+        // square and multiply from the lowest bit of the exponent
+        let bits = exp.len() * 8 - exp.bit_leading_zeros();
+        for bit in exp.bit_iter().rev().take(bits) {
+            if bit {
+                result.bit_mul_mod(&base, modulus);
+            }
+            let square = base.clone();
+            base.bit_mul_mod(&square, modulus);
+        }
+
+        let (len, n) = (self.len(), self.len().min(result.len()));
+        let (high, low) = result.split_at(result.len() - n);
+        self.fill(0);
+        self[len - n..].copy_from_slice(low);
+        !high.bit_all_zero()
+    }
+
+    fn bit_is_probable_prime(&self, rounds: usize) -> bool {
+        let n = &self[self.bit_leading_zeros() / 8..];
+        if n.bit_be_cmp(&[2]) == Ordering::Less {
+            return false;
+        }
+        for p in SMALL_PRIMES {
+            if n.rem_u32(p) == 0 {
+                return n.bit_be_cmp(&p.to_be_bytes()) == Ordering::Equal;
+            }
+        }
+
+        // n - 1 = d * 2^s
+        let mut n_1 = n.to_vec();
+        n_1.bit_be_sub(&[1]);
+        let s = n_1.bit_trailing_zeros();
+        let mut d = n_1.clone();
+        d.bit_shr(s);
+
+        let witness = |base: &[u8]| {
+            let mut x = base.mod_reduce(n);
+            x.bit_mod_pow(&d, n);
+            if x.bit_be_cmp(&[1]) == Ordering::Equal || x == n_1 {
+                return false;
+            }
+            for _ in 1..s {
+                let square = x.clone();
+                x.bit_mul_mod(&square, n);
+                if x == n_1 {
+                    return false;
+                }
+            }
+            true
+        };
+
+        if n.len() <= 8 {
+            BASES_64.iter().all(|&a| !witness(&[a]))
+        } else {
+            // random bases in 2..=n-2
+            let mut n_3 = n.to_vec();
+            n_3.bit_be_sub(&[3]);
+            let mut rng = SplitMix64::new();
+            (0..rounds).all(|_| {
+                let mut a = vec![0; n.len() + 8];
+                rng.fill(&mut a);
+                a.bit_be_rem(&n_3);
+                a.bit_be_add(&[2]);
+                !witness(&a)
+            })
+        }
+    }
+
+    fn bit_next_prime(&mut self) -> bool {
+        if self.is_empty() {
+            return true; // no prime fits in zero bytes
+        }
+        let mut candidate = self.to_vec();
+        if candidate.bit_be_cmp(&[2]) == Ordering::Less {
+            candidate.fill(0);
+            candidate.bit_be_add(&[2]);
+            self.copy_from_slice(&candidate);
+            return false;
+        }
+
+        // step over odd numbers only
+        let step = if candidate.bit_get(candidate.len() * 8 - 1) {
+            [2]
+        } else {
+            [1]
+        };
+        if candidate.bit_be_add(&step) {
+            return true;
+        }
+        while !candidate.bit_is_probable_prime(PRIME_ROUNDS) {
+            if candidate.bit_be_add(&[2]) {
+                return true;
+            }
+        }
+        self.copy_from_slice(&candidate);
+        false
+    }
+}
+
+/// Generate a random probable prime of exactly `bits` bits
+/// # Security
+/// **Not cryptographically secure, do not use it for keys.**
+/// The candidates are drawn from a SplitMix64 generator seeded by the standard library hasher keys,
+/// use `random_prime_with` and a cryptographic random source for keys.
+/// # Parameters
+/// - `bits`: the bit length of the prime, `bits >= 2`
+/// # Returns
+/// - big-endian bytes with leading zero bits padded to a whole byte
+/// # Examples
+/// ```
+/// # use xbits::core::{random_prime, BitPrime, Bitwise};
+/// let p = random_prime(80);
+/// assert_eq!(p.len(), 10);
+/// assert_eq!(p.bit_leading_zeros(), 0);
+/// assert_eq!(p.bit_is_probable_prime(16), true);
+/// ```
+pub fn random_prime(bits: usize) -> Vec<u8> {
+    let mut rng = SplitMix64::new();
+    random_prime_with(bits, |buf| rng.fill(buf))
+}
+
+/// Generate a random probable prime of exactly `bits` bits,
+/// the candidates are filled by the random source `fill`
+/// # Parameters
+/// - `bits`: the bit length of the prime, `bits >= 2`
+/// - `fill`: fills the buffer with random bytes, a cryptographic source for keys,
+///   e.g. `getrandom::fill` or `RngCore::fill_bytes` of an OS random generator
+/// # Returns
+/// - big-endian bytes with leading zero bits padded to a whole byte
+/// # Examples
+/// ```
+/// # use xbits::core::{random_prime_with, BitPrime};
+/// // a counter is not random, only for the example
+/// let mut counter = 0_u8;
+/// let p = random_prime_with(16, |buf| {
+///     buf.iter_mut().for_each(|b| (*b, counter) = (counter, counter.wrapping_add(1)));
+/// });
+/// assert_eq!(p, 33797_u16.to_be_bytes());
+/// assert_eq!(p.bit_is_probable_prime(0), true);
+/// ```
+pub fn random_prime_with(bits: usize, mut fill: impl FnMut(&mut [u8])) -> Vec<u8> {
+    assert_range!(bits, 2, usize::MAX, "random_prime");
+    let n = bits.div_ceil(8);
+    let padding = n * 8 - bits;
+    let mut candidate = vec![0; n];
+    loop {
+        fill(&mut candidate);
+        candidate[0] &= 0xff >> padding;
+        candidate.bit_set(padding, true);
+        if bits > 2 {
+            candidate.bit_set(n * 8 - 1, true);
+        }
+        if candidate.bit_is_probable_prime(PRIME_ROUNDS) {
+            return candidate;
+        }
+    }
+}
+
+trait ModExtend {
+    /// Reduce modulo `modulus` in a buffer of the modulus length
+    fn mod_reduce(&self, modulus: &[u8]) -> Vec<u8>;
+
+    /// Modular multiplication `self = self * other % modulus`
+    fn bit_mul_mod(&mut self, other: &[u8], modulus: &[u8]);
+
+    /// Remainder of division by a small divisor
+    fn rem_u32(&self, divisor: u32) -> u32;
+}
+
+impl ModExtend for [u8] {
+    fn mod_reduce(&self, modulus: &[u8]) -> Vec<u8> {
+        let mut value = self.to_vec();
+        value.bit_be_rem(modulus);
+        let n = modulus.len();
+        let mut data = vec![0; n];
+        let m = value.len().min(n);
+        data[n - m..].copy_from_slice(&value[value.len() - m..]);
+        data
+    }
+
+    fn bit_mul_mod(&mut self, other: &[u8], modulus: &[u8]) {
+        // double width product never overflows
+        let mut product = vec![0; self.len() + other.len()];
+        product[other.len()..].copy_from_slice(self);
+        product.bit_be_mul(other);
+        product.bit_be_rem(modulus);
+        let n = self.len();
+        self.copy_from_slice(&product[product.len() - n..]);
+    }
+
+    fn rem_u32(&self, divisor: u32) -> u32 {
+        self.iter()
+            .fold(0_u64, |rem, &b| ((rem << 8) | b as u64) % divisor as u64) as u32
+    }
+}
+
+/// Random number generator for Miller-Rabin bases and the candidates of `random_prime`,
+/// seeded by the randomized keys of the standard library hasher, not cryptographically secure.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u64(0x9e37_79b9_7f4a_7c15);
+        SplitMix64(hasher.finish())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_be_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod test_prime {
+    use super::*;
+
+    fn is_prime(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        for (a, e, m) in [
+            (4_u64, 13_u64, 497_u64),
+            (2, 0, 7),
+            (0, 0, 1),
+            (3, 200, 1000),
+            (7, 1, 5),
+        ] {
+            let mut x = a.to_be_bytes();
+            assert_eq!(x.bit_mod_pow(&e.to_be_bytes(), &m.to_be_bytes()), false);
+            let expect = (0..e).fold(1 % m, |acc, _| acc * a % m);
+            assert_eq!(x, expect.to_be_bytes(), "{a} ^ {e} % {m}");
+        }
+        assert_eq!([3].bit_mod_pow(&[1], &[0]), true);
+
+        // the result is longer than `self`
+        let mut x = [200];
+        assert_eq!(x.bit_mod_pow(&[2], &[1, 0, 0]), true);
+        assert_eq!(x, [40000_u32 as u8]);
+        let mut x = [2];
+        assert_eq!(x.bit_mod_pow(&[7], &[1, 0, 0]), false);
+        assert_eq!(x, [128]);
+    }
+
+    #[test]
+    fn test_probable_prime() {
+        for n in (0..2000_u64).chain(1_000_000..1_001_000) {
+            assert_eq!(n.to_be_bytes().bit_is_probable_prime(8), is_prime(n), "{n}");
+        }
+        // strong pseudoprimes to several bases
+        for n in [3_215_031_751_u64, 3_825_123_056_546_413_051] {
+            assert_eq!(n.to_be_bytes().bit_is_probable_prime(8), false);
+        }
+        assert_eq!(
+            ((1_u128 << 127) - 1).to_be_bytes().bit_is_probable_prime(8),
+            true
+        );
+        assert_eq!(
+            ((1_u128 << 67) - 1).to_be_bytes().bit_is_probable_prime(8),
+            false
+        );
+    }
+
+    #[test]
+    fn test_next_prime() {
+        for n in [0_u64, 1, 2, 3, 4, 13, 24, 89, 1000] {
+            let mut x = n.to_be_bytes();
+            assert_eq!(x.bit_next_prime(), false);
+            let next = (n + 1..).find(|&p| is_prime(p)).unwrap();
+            assert_eq!(x, next.to_be_bytes(), "{n}");
+        }
+        assert_eq!([0xfc].bit_next_prime(), true);
+        assert_eq!([0xf0].bit_next_prime(), false);
+        assert_eq!([].bit_next_prime(), true);
+    }
+
+    #[test]
+    fn test_random_prime() {
+        for bits in [2, 3, 8, 13, 64, 65] {
+            let p = random_prime(bits);
+            assert_eq!(p.len() * 8 - p.bit_leading_zeros(), bits);
+            assert!(p.bit_is_probable_prime(8));
+
+            // the candidates are taken from the random source
+            let mut state = bits as u64;
+            let p = random_prime_with(bits, |buf| {
+                buf.iter_mut().for_each(|b| {
+                    state = state
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1);
+                    *b = (state >> 56) as u8;
+                })
+            });
+            assert_eq!(p.len() * 8 - p.bit_leading_zeros(), bits);
+            assert!(p.bit_is_probable_prime(8));
+        }
+    }
+}
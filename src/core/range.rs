@@ -1,16 +1,490 @@
+use super::{BitOrder, Bitwise, Msb0};
+use crate::assert_range;
 use std::ops::Range;
 
+/**
+ * Bit range operations implementation for `[u8]`
+ * The range is in bit indexes, the partial bytes at both ends are masked
+ * and the whole bytes between them are processed a word at a time.
+ */
 pub trait BitRange {
+    /// Count the number of one bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0001_1000, 0b1000_0011].bit_count_ones_range(4..9), 2);
+    /// ```
+    fn bit_count_ones_range(&self, range: Range<usize>) -> usize;
+
+    /// Count the number of zero bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0001_1000, 0b1000_0011].bit_count_zeros_range(4..9), 3);
+    /// ```
+    fn bit_count_zeros_range(&self, range: Range<usize>) -> usize;
+
+    /// Parity of the one bits in the range
+    /// # Returns
+    /// - `true` if the number of one bits is odd
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0001_1000, 0b1000_0011].bit_parity_range(4..8), true);
+    /// ```
+    fn bit_parity_range(&self, range: Range<usize>) -> bool;
+
+    /// Count the number of different bits in the same range of two buffers
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0001_1000, 0b1000_0011].bit_hamming_distance_range(4..9, &[0, 0]), 2);
+    /// ```
+    fn bit_hamming_distance_range(&self, range: Range<usize>, other: &Self) -> usize;
+
+    /// Get the bits in the range
+    fn bit_get_range(&self, range: Range<usize>) -> Vec<bool>;
+
+    /// Set all bits in the range to `value`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0, 0].bit_set_range(4..9, true), [0b0000_1111, 0b1000_0000]);
+    /// ```
+    fn bit_set_range(&mut self, range: Range<usize>, value: bool) -> &mut Self;
+
+    /// Shift the bits in the range to the left by one bit, the last bit of the range is set to `0`
+    /// # Returns
+    /// - the first bit of the range, which is shifted out, `false` if the range is empty
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// let mut data = [0b0000_1011, 0b1000_0000];
+    /// assert_eq!(data.bit_shl_range(4..9), true);
+    /// assert_eq!(data, [0b0000_0111, 0b0000_0000]);
+    /// ```
     fn bit_shl_range(&mut self, range: Range<usize>) -> bool;
+
+    /// Shift the bits in the range to the right by one bit, the first bit of the range is set to `0`
+    /// # Returns
+    /// - the last bit of the range, which is shifted out, `false` if the range is empty
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// let mut data = [0b0000_1011, 0b1000_0000];
+    /// assert_eq!(data.bit_shr_range(4..9), true);
+    /// assert_eq!(data, [0b0000_0101, 0b1000_0000]);
+    /// ```
     fn bit_shr_range(&mut self, range: Range<usize>) -> bool;
 
+    /// Reverse the order of the bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0000_1100, 0b0000_0000].bit_reverse_range(4..9), [0b0000_0001, 0b1000_0000]);
+    /// ```
     fn bit_reverse_range(&mut self, range: Range<usize>) -> &mut Self;
+
+    /// Bitwise operator `!` on the bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0000_1111, 0].bit_not_range(4..9), [0b0000_0000, 0b1000_0000]);
+    /// ```
     fn bit_not_range(&mut self, range: Range<usize>) -> &mut Self;
 
+    /// Bitwise operator `|` on the bits in the range with the same range of `other`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0000_0000, 0].bit_or_range(4..9, &[0xff, 0xff]), [0b0000_1111, 0b1000_0000]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits of `self` or `other`
     fn bit_or_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self;
+
+    /// Bitwise operator `&` on the bits in the range with the same range of `other`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0xff, 0xff].bit_and_range(4..9, &[0, 0]), [0b1111_0000, 0b0111_1111]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits of `self` or `other`
     fn bit_and_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self;
+
+    /// Bitwise operator `^` on the bits in the range with the same range of `other`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitRange;
+    /// assert_eq!([0b0000_1111, 0].bit_xor_range(4..9, &[0xff, 0xff]), [0b0000_0000, 0b1000_0000]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits of `self` or `other`
     fn bit_xor_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self;
+}
 
-    fn bit_get_range(&self, range: Range<usize>) -> Vec<bool>;
-    fn bit_set_range(&mut self, range: Range<usize>, value: bool) -> &mut Self;
+impl BitRange for [u8] {
+    fn bit_count_ones_range(&self, range: Range<usize>) -> usize {
+        self.range_count_ones::<Msb0>(range)
+    }
+
+    fn bit_count_zeros_range(&self, range: Range<usize>) -> usize {
+        range.len() - self.range_count_ones::<Msb0>(range)
+    }
+
+    fn bit_parity_range(&self, range: Range<usize>) -> bool {
+        self.range_parity::<Msb0>(range)
+    }
+
+    fn bit_hamming_distance_range(&self, range: Range<usize>, other: &Self) -> usize {
+        self.range_hamming_distance::<Msb0>(range.clone(), other, range)
+    }
+
+    fn bit_get_range(&self, range: Range<usize>) -> Vec<bool> {
+        range.map(|i| self.bit_get(i)).collect()
+    }
+
+    fn bit_set_range(&mut self, range: Range<usize>, value: bool) -> &mut Self {
+        self.range_fill::<Msb0>(range, value);
+        self
+    }
+
+    fn bit_shl_range(&mut self, range: Range<usize>) -> bool {
+        let (start, end, bits) = (range.start, range.end, self.len() * 8);
+        assert_range!(end, start, bits, "bit range");
+        if range.is_empty() {
+            return false;
+        }
+        let out = self.bit_get(start);
+        self.bit_copy_within(start + 1..end, start);
+        self.bit_set(end - 1, false);
+        out
+    }
+
+    fn bit_shr_range(&mut self, range: Range<usize>) -> bool {
+        let (start, end, bits) = (range.start, range.end, self.len() * 8);
+        assert_range!(end, start, bits, "bit range");
+        if range.is_empty() {
+            return false;
+        }
+        let out = self.bit_get(end - 1);
+        self.bit_copy_within(start..end - 1, start + 1);
+        self.bit_set(start, false);
+        out
+    }
+
+    fn bit_reverse_range(&mut self, range: Range<usize>) -> &mut Self {
+        let (start, end, bits) = (range.start, range.end, self.len() * 8);
+        assert_range!(end, start, bits, "bit range");
+        for i in 0..range.len() / 2 {
+            let (a, b) = (self.bit_get(start + i), self.bit_get(end - 1 - i));
+            self.bit_set(start + i, b).bit_set(end - 1 - i, a);
+        }
+        self
+    }
+
+    fn bit_not_range(&mut self, range: Range<usize>) -> &mut Self {
+        self.range_not::<Msb0>(range);
+        self
+    }
+
+    fn bit_or_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self {
+        let mid = zip_ends(self, range, other, |a, b| a | b);
+        self[mid.clone()].bit_be_or(&other[mid]);
+        self
+    }
+
+    fn bit_and_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self {
+        let mid = zip_ends(self, range, other, |a, b| a & b);
+        self[mid.clone()].bit_be_and(&other[mid]);
+        self
+    }
+
+    fn bit_xor_range(&mut self, range: Range<usize>, other: &Self) -> &mut Self {
+        let mid = zip_ends(self, range, other, |a, b| a ^ b);
+        self[mid.clone()].bit_be_xor(&other[mid]);
+        self
+    }
+}
+
+/// Bit range operations in any bit order, shared with the range views
+pub(crate) trait RangeExtend {
+    fn range_count_ones<O: BitOrder>(&self, range: Range<usize>) -> usize;
+    fn range_parity<O: BitOrder>(&self, range: Range<usize>) -> bool;
+    fn range_hamming_distance<O: BitOrder>(
+        &self,
+        range: Range<usize>,
+        other: &[u8],
+        other_range: Range<usize>,
+    ) -> usize;
+    fn range_fill<O: BitOrder>(&mut self, range: Range<usize>, value: bool);
+    fn range_not<O: BitOrder>(&mut self, range: Range<usize>);
+}
+
+impl RangeExtend for [u8] {
+    fn range_count_ones<O: BitOrder>(&self, range: Range<usize>) -> usize {
+        let span = Span::new::<O>(&range, self.len());
+        let ends = span.head(self).count_ones() + span.tail(self).count_ones();
+        ends as usize + self[span.mid].bit_count_ones()
+    }
+
+    fn range_parity<O: BitOrder>(&self, range: Range<usize>) -> bool {
+        let span = Span::new::<O>(&range, self.len());
+        let ends = (span.head(self) ^ span.tail(self)).count_ones() % 2 == 1;
+        ends ^ self[span.mid].bit_parity()
+    }
+
+    fn range_hamming_distance<O: BitOrder>(
+        &self,
+        range: Range<usize>,
+        other: &[u8],
+        other_range: Range<usize>,
+    ) -> usize {
+        assert_eq!(
+            range.len(),
+            other_range.len(),
+            "[xbits] hamming_distance ranges in different lengths"
+        );
+        if range.start % 8 != other_range.start % 8 {
+            // not aligned in bytes, compare bit by bit
+            return range
+                .zip(other_range)
+                .filter(|&(i, j)| self.bit_get_in::<O>(i) != other.bit_get_in::<O>(j))
+                .count();
+        }
+        let (a, b) = (
+            Span::new::<O>(&range, self.len()),
+            Span::new::<O>(&other_range, other.len()),
+        );
+        let ends = (a.head(self) ^ b.head(other)).count_ones()
+            + (a.tail(self) ^ b.tail(other)).count_ones();
+        ends as usize + self[a.mid].bit_hamming_distance(&other[b.mid])
+    }
+
+    fn range_fill<O: BitOrder>(&mut self, range: Range<usize>, value: bool) {
+        let span = Span::new::<O>(&range, self.len());
+        for (i, mask) in [span.head, span.tail] {
+            match (mask, value) {
+                (0, _) => {}
+                (_, true) => self[i] |= mask,
+                (_, false) => self[i] &= !mask,
+            }
+        }
+        self[span.mid].bit_fill(value);
+    }
+
+    fn range_not<O: BitOrder>(&mut self, range: Range<usize>) {
+        let span = Span::new::<O>(&range, self.len());
+        for (i, mask) in [span.head, span.tail] {
+            if mask != 0 {
+                self[i] ^= mask;
+            }
+        }
+        self[span.mid].bit_not();
+    }
+}
+
+/// Apply `op` to the bits of the partial bytes at both ends of the range,
+/// returns the whole bytes between them
+fn zip_ends(
+    data: &mut [u8],
+    range: Range<usize>,
+    other: &[u8],
+    op: impl Fn(u8, u8) -> u8,
+) -> Range<usize> {
+    let span = Span::new::<Msb0>(&range, data.len());
+    Span::new::<Msb0>(&range, other.len());
+    for (i, mask) in [span.head, span.tail] {
+        if mask != 0 {
+            data[i] = data[i] & !mask | op(data[i], other[i]) & mask;
+        }
+    }
+    span.mid
+}
+
+/// A bit range split into the partial bytes at both ends and the whole bytes between them
+struct Span {
+    head: (usize, u8), // byte index and mask of the first byte
+    mid: Range<usize>, // whole bytes
+    tail: (usize, u8), // byte index and mask of the last byte, the mask is zero if none
+}
+
+impl Span {
+    fn new<O: BitOrder>(range: &Range<usize>, bytes: usize) -> Self {
+        let (start, end, bits) = (range.start, range.end, bytes * 8);
+        assert_range!(end, start, bits, "bit range");
+        if start == end {
+            return Span {
+                head: (0, 0),
+                mid: 0..0,
+                tail: (0, 0),
+            };
+        }
+
+        let (first, last) = (start / 8, (end - 1) / 8);
+        if first == last {
+            Span {
+                head: (first, O::range_mask(start % 8, end - first * 8)),
+                mid: 0..0,
+                tail: (0, 0),
+            }
+        } else {
+            Span {
+                head: (first, O::range_mask(start % 8, 8)),
+                mid: first + 1..last,
+                tail: (last, O::range_mask(0, end - last * 8)),
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn head(&self, data: &[u8]) -> u8 {
+        Self::masked(data, self.head)
+    }
+
+    #[inline(always)]
+    fn tail(&self, data: &[u8]) -> u8 {
+        Self::masked(data, self.tail)
+    }
+
+    #[inline(always)]
+    fn masked(data: &[u8], (i, mask): (usize, u8)) -> u8 {
+        if mask == 0 { 0 } else { data[i] & mask }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod test_range {
+    use super::*;
+    use crate::core::Lsb0;
+    use crate::test_util::bytes;
+
+    #[test]
+    fn test_range_count() {
+        let data = bytes(21, 0);
+        let other: Vec<u8> = data.iter().map(|b| b.rotate_left(3)).collect();
+        for start in [0, 1, 7, 8, 13, 40] {
+            for end in [start, start + 1, start + 7, 64, 99, 168] {
+                let range = start..end;
+                let bits: Vec<bool> = range.clone().map(|i| data.bit_get(i)).collect();
+                let ones = bits.iter().filter(|&&b| b).count();
+                assert_eq!(data.bit_get_range(range.clone()), bits);
+                assert_eq!(data.bit_count_ones_range(range.clone()), ones);
+                assert_eq!(
+                    data.bit_count_zeros_range(range.clone()),
+                    range.len() - ones
+                );
+                assert_eq!(data.bit_parity_range(range.clone()), ones % 2 == 1);
+
+                let diff = range
+                    .clone()
+                    .filter(|&i| data.bit_get(i) != other.bit_get(i))
+                    .count();
+                assert_eq!(data.bit_hamming_distance_range(range.clone(), &other), diff);
+
+                let lsb = range
+                    .clone()
+                    .filter(|&i| data.bit_get_in::<Lsb0>(i))
+                    .count();
+                assert_eq!(data.range_count_ones::<Lsb0>(range.clone()), lsb);
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_set() {
+        for range in [0..0, 3..5, 5..16, 8..16, 7..33, 0..40] {
+            let mut data = [0; 5];
+            data.bit_set_range(range.clone(), true);
+            assert_eq!(data.bit_count_ones(), range.len());
+            assert_eq!(data.bit_count_ones_range(range.clone()), range.len());
+            data.bit_not_range(0..40);
+            assert_eq!(data.bit_count_zeros_range(range.clone()), range.len());
+            data.bit_set_range(range.clone(), true);
+            assert_eq!(data.bit_all_one(), true);
+
+            let mut data = [0; 5];
+            data.range_fill::<Lsb0>(range.clone(), true);
+            let expect: Vec<bool> = (0..40).map(|i| range.contains(&i)).collect();
+            assert_eq!(
+                (0..40)
+                    .map(|i| data.bit_get_in::<Lsb0>(i))
+                    .collect::<Vec<_>>(),
+                expect
+            );
+        }
+    }
+
+    #[test]
+    fn test_range_ops() {
+        let data = bytes(21, 0);
+        let other: Vec<u8> = data.iter().map(|b| b.rotate_left(3)).collect();
+        let get = |d: &[u8], r: Range<usize>| d.bit_get_range(r);
+        for start in [0, 1, 7, 8, 13, 40] {
+            for end in [start, start + 1, start + 7, 64, 99, 168] {
+                let range = start..end;
+                let bits = get(&data, range.clone());
+                let outside = |d: &[u8]| {
+                    (0..168)
+                        .filter(|i| !range.contains(i))
+                        .map(|i| d.bit_get(i))
+                        .collect::<Vec<_>>()
+                };
+                let check = |d: &[u8], expected: Vec<bool>| {
+                    assert_eq!(get(d, range.clone()), expected, "range {range:?}");
+                    assert_eq!(outside(d), outside(&data), "range {range:?}");
+                };
+
+                let mut shl = data.clone();
+                assert_eq!(
+                    shl.bit_shl_range(range.clone()),
+                    bits.first() == Some(&true)
+                );
+                let expected = bits.iter().skip(1).copied().chain([false]);
+                check(&shl, expected.take(range.len()).collect());
+
+                let mut shr = data.clone();
+                assert_eq!(shr.bit_shr_range(range.clone()), bits.last() == Some(&true));
+                let expected = [false].into_iter().chain(bits.iter().copied());
+                check(&shr, expected.take(range.len()).collect());
+
+                let mut reverse = data.clone();
+                reverse.bit_reverse_range(range.clone());
+                check(&reverse, bits.iter().rev().copied().collect());
+
+                let zip = |op: fn(bool, bool) -> bool| {
+                    range
+                        .clone()
+                        .map(|i| op(data.bit_get(i), other.bit_get(i)))
+                        .collect()
+                };
+                check(
+                    data.clone().bit_or_range(range.clone(), &other),
+                    zip(|a, b| a | b),
+                );
+                check(
+                    data.clone().bit_and_range(range.clone(), &other),
+                    zip(|a, b| a & b),
+                );
+                check(
+                    data.clone().bit_xor_range(range.clone(), &other),
+                    zip(|a, b| a ^ b),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_range_other_bounds() {
+        [0_u8; 3].bit_or_range(3..17, &[0xff; 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_range_bounds() {
+        [0_u8; 2].bit_count_ones_range(3..17);
+    }
 }
@@ -0,0 +1,386 @@
+use super::{BitOrder, Bitwise, Msb0};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/**
+ * Bit position search implementation for `[u8]`
+ * The positions are found a word at a time, whole words of the other bit value are skipped.
+ * `bit_first_one` and `bit_last_one` generalize `bit_leading_zeros` and `bit_trailing_zeros`.
+ */
+pub trait BitSearch {
+    /// Iterator the indexes of the one bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// let data = [0b1000_0000, 0b0100_0001];
+    /// assert_eq!(data.bit_ones().collect::<Vec<_>>(), [0, 9, 15]);
+    /// assert_eq!(data.bit_ones().rev().collect::<Vec<_>>(), [15, 9, 0]);
+    /// ```
+    fn bit_ones(&self) -> Positions<'_>;
+
+    /// Iterator the indexes of the zero bits
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!([0b1111_0110].bit_zeros().collect::<Vec<_>>(), [4, 7]);
+    /// ```
+    fn bit_zeros(&self) -> Positions<'_>;
+
+    /// Iterator the runs of the same bits as `(value, start, len)`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!(
+    ///     [0b1110_0000, 0b0000_0001].bit_runs().collect::<Vec<_>>(),
+    ///     [(true, 0, 3), (false, 3, 12), (true, 15, 1)]
+    /// );
+    /// ```
+    fn bit_runs(&self) -> Runs<'_>;
+
+    /// Iterator the indexes of the one bits in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// # use xbits::Lsb0;
+    /// assert_eq!([0b1000_0001].bit_ones_in::<Lsb0>().collect::<Vec<_>>(), [0, 7]);
+    /// ```
+    fn bit_ones_in<O: BitOrder>(&self) -> Positions<'_, O>;
+
+    /// Iterator the indexes of the zero bits in the bit order `O`
+    fn bit_zeros_in<O: BitOrder>(&self) -> Positions<'_, O>;
+
+    /// Iterator the runs of the same bits in the bit order `O`
+    fn bit_runs_in<O: BitOrder>(&self) -> Runs<'_, O>;
+
+    /// Index of the first one bit, same as `bit_leading_zeros` if any
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!([0b0000_0000, 0b0001_0011].bit_first_one(), Some(11));
+    /// assert_eq!([0b0000_0000, 0b0000_0000].bit_first_one(), None);
+    /// ```
+    fn bit_first_one(&self) -> Option<usize>;
+
+    /// Index of the last one bit, `bits - 1 - bit_trailing_zeros` if any
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!([0b1111_1000, 0b0000_0000].bit_last_one(), Some(4));
+    /// ```
+    fn bit_last_one(&self) -> Option<usize>;
+
+    /// Index of the first one bit after the index `i`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!([0b1000_0000, 0b0100_0001].bit_next_one_after(0), Some(9));
+    /// assert_eq!([0b1000_0000, 0b0100_0001].bit_next_one_after(15), None);
+    /// ```
+    fn bit_next_one_after(&self, i: usize) -> Option<usize>;
+
+    /// Index of the last one bit before the index `i`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSearch;
+    /// assert_eq!([0b1000_0000, 0b0100_0001].bit_prev_one_before(9), Some(0));
+    /// assert_eq!([0b1000_0000, 0b0100_0001].bit_prev_one_before(0), None);
+    /// ```
+    fn bit_prev_one_before(&self, i: usize) -> Option<usize>;
+}
+
+impl BitSearch for [u8] {
+    fn bit_ones(&self) -> Positions<'_> {
+        self.bit_ones_in::<Msb0>()
+    }
+
+    fn bit_zeros(&self) -> Positions<'_> {
+        self.bit_zeros_in::<Msb0>()
+    }
+
+    fn bit_runs(&self) -> Runs<'_> {
+        self.bit_runs_in::<Msb0>()
+    }
+
+    fn bit_ones_in<O: BitOrder>(&self) -> Positions<'_, O> {
+        Positions::new(self, 0..self.len() * 8, true)
+    }
+
+    fn bit_zeros_in<O: BitOrder>(&self) -> Positions<'_, O> {
+        Positions::new(self, 0..self.len() * 8, false)
+    }
+
+    fn bit_runs_in<O: BitOrder>(&self) -> Runs<'_, O> {
+        Runs::new(self, 0..self.len() * 8)
+    }
+
+    fn bit_first_one(&self) -> Option<usize> {
+        self.bit_ones().next()
+    }
+
+    fn bit_last_one(&self) -> Option<usize> {
+        self.bit_ones().next_back()
+    }
+
+    fn bit_next_one_after(&self, i: usize) -> Option<usize> {
+        let bits = self.len() * 8;
+        Positions::<Msb0>::new(self, i.saturating_add(1).min(bits)..bits, true).next()
+    }
+
+    fn bit_prev_one_before(&self, i: usize) -> Option<usize> {
+        Positions::<Msb0>::new(self, 0..i.min(self.len() * 8), true).next_back()
+    }
+}
+
+/// Iterator of the indexes of the one or zero bits
+#[derive(Debug, Clone)]
+pub struct Positions<'a, O: BitOrder = Msb0> {
+    data: &'a [u8],
+    range: Range<usize>, // bits not iterated yet
+    value: bool,
+    order: PhantomData<O>,
+}
+
+impl<'a, O: BitOrder> Positions<'a, O> {
+    /// Find the `value` bits in the `range` of the data, the range is in the data
+    #[inline(always)]
+    pub(crate) fn new(data: &'a [u8], range: Range<usize>, value: bool) -> Self {
+        debug_assert!(range.end <= data.len() * 8);
+        Positions {
+            data,
+            range,
+            value,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<O: BitOrder> Iterator for Positions<'_, O> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let i = find_first::<O>(self.data, self.range.clone(), self.value)?;
+        self.range.start = i + 1;
+        Some(i)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.range.len()))
+    }
+}
+
+impl<O: BitOrder> DoubleEndedIterator for Positions<'_, O> {
+    fn next_back(&mut self) -> Option<usize> {
+        let i = find_last::<O>(self.data, self.range.clone(), self.value)?;
+        self.range.end = i;
+        Some(i)
+    }
+}
+
+impl<O: BitOrder> FusedIterator for Positions<'_, O> {}
+
+/// Iterator of the runs of the same bits as `(value, start, len)`
+#[derive(Debug, Clone)]
+pub struct Runs<'a, O: BitOrder = Msb0> {
+    data: &'a [u8],
+    range: Range<usize>, // bits not iterated yet
+    order: PhantomData<O>,
+}
+
+impl<'a, O: BitOrder> Runs<'a, O> {
+    /// Split the `range` of the data into runs, the range is in the data
+    #[inline(always)]
+    pub(crate) fn new(data: &'a [u8], range: Range<usize>) -> Self {
+        debug_assert!(range.end <= data.len() * 8);
+        Runs {
+            data,
+            range,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<O: BitOrder> Iterator for Runs<'_, O> {
+    type Item = (bool, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Range { start, end } = self.range;
+        if start >= end {
+            return None;
+        }
+        let value = self.data.bit_get_in::<O>(start);
+        let stop = find_first::<O>(self.data, start..end, !value).unwrap_or(end);
+        self.range.start = stop;
+        Some((value, start, stop - start))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.range.len().min(1), Some(self.range.len()))
+    }
+}
+
+impl<O: BitOrder> DoubleEndedIterator for Runs<'_, O> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let Range { start, end } = self.range;
+        if start >= end {
+            return None;
+        }
+        let value = self.data.bit_get_in::<O>(end - 1);
+        let first = find_last::<O>(self.data, start..end, !value).map_or(start, |i| i + 1);
+        self.range.end = first;
+        Some((value, first, end - first))
+    }
+}
+
+impl<O: BitOrder> FusedIterator for Runs<'_, O> {}
+
+/// Read the word `i` of the bytes with the bit `j` of the bytes at the bit `j % 64` of the word,
+/// the bytes after the end are read as zeros
+#[inline(always)]
+pub(crate) fn indexed_word<O: BitOrder>(data: &[u8], i: usize) -> u64 {
+    let start = i * 8;
+    match data.get(start..start + 8) {
+        Some(bytes) => O::load_indexed(bytes.try_into().unwrap()),
+        None => {
+            let mut bytes = [0; 8];
+            bytes[..data.len() - start].copy_from_slice(&data[start..]);
+            O::load_indexed(bytes)
+        }
+    }
+}
+
+/// The word `w` of the bytes masked to the `range`, inverted to find the zero bits
+#[inline(always)]
+fn masked_word<O: BitOrder>(data: &[u8], w: usize, range: &Range<usize>, value: bool) -> u64 {
+    let word = indexed_word::<O>(data, w);
+    let word = if value { word } else { !word };
+    let (low, high) = (w * WORD_BITS, (w + 1) * WORD_BITS);
+    let mut mask = u64::MAX;
+    if range.start > low {
+        mask &= u64::MAX << (range.start - low);
+    }
+    if range.end < high {
+        mask &= u64::MAX >> (high - range.end);
+    }
+    word & mask
+}
+
+/// Index of the first `value` bit in the range
+fn find_first<O: BitOrder>(data: &[u8], range: Range<usize>, value: bool) -> Option<usize> {
+    if range.is_empty() {
+        return None;
+    }
+    let words = range.start / WORD_BITS..(range.end - 1) / WORD_BITS + 1;
+    words.into_iter().find_map(|w| {
+        let word = masked_word::<O>(data, w, &range, value);
+        (word != 0).then(|| w * WORD_BITS + word.trailing_zeros() as usize)
+    })
+}
+
+/// Index of the last `value` bit in the range
+fn find_last<O: BitOrder>(data: &[u8], range: Range<usize>, value: bool) -> Option<usize> {
+    if range.is_empty() {
+        return None;
+    }
+    let words = range.start / WORD_BITS..(range.end - 1) / WORD_BITS + 1;
+    words.into_iter().rev().find_map(|w| {
+        let word = masked_word::<O>(data, w, &range, value);
+        (word != 0).then(|| w * WORD_BITS + (WORD_BITS - 1) - word.leading_zeros() as usize)
+    })
+}
+
+#[cfg(test)]
+mod test_search {
+    use super::*;
+    use crate::core::{BitIterator, Lsb0};
+    use crate::test_util;
+
+    /// Sparse bytes with long runs of zeros
+    fn bytes(len: usize) -> Vec<u8> {
+        test_util::bytes(len, 0)
+            .into_iter()
+            .map(|b| if b % 3 == 0 { 0 } else { b & 0x91 })
+            .collect()
+    }
+
+    fn positions<O: BitOrder>(data: &[u8], value: bool) -> Vec<usize> {
+        let bits = test_util::bits::<O>(data).into_iter().enumerate();
+        bits.filter(|&(_, b)| b == value).map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn test_positions() {
+        for len in [0, 1, 7, 8, 9, 31, 100] {
+            let data = bytes(len);
+            let ones = positions::<Msb0>(&data, true);
+            assert_eq!(data.bit_ones().collect::<Vec<_>>(), ones);
+            assert_eq!(
+                data.bit_zeros().collect::<Vec<_>>(),
+                positions::<Msb0>(&data, false)
+            );
+            assert_eq!(
+                data.bit_ones_in::<Lsb0>().rev().collect::<Vec<_>>(),
+                positions::<Lsb0>(&data, true)
+                    .into_iter()
+                    .rev()
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                data.bit_zeros_in::<Lsb0>().collect::<Vec<_>>(),
+                positions::<Lsb0>(&data, false)
+            );
+
+            // both ends meet in the middle
+            let mut iter = data.bit_ones();
+            let (mut front, mut back) = (vec![], vec![]);
+            while let Some(i) = iter.next() {
+                front.push(i);
+                back.extend(iter.next_back());
+            }
+            front.extend(back.into_iter().rev());
+            assert_eq!(front, ones);
+
+            assert_eq!(data.bit_first_one(), ones.first().copied());
+            assert_eq!(data.bit_last_one(), ones.last().copied());
+            if !ones.is_empty() {
+                assert_eq!(data.bit_first_one(), Some(data.bit_leading_zeros()));
+                assert_eq!(
+                    data.bit_last_one(),
+                    Some(len * 8 - 1 - data.bit_trailing_zeros())
+                );
+            }
+            for i in 0..len * 8 + 2 {
+                let next = ones.iter().find(|&&j| j > i).copied();
+                let prev = ones.iter().rfind(|&&j| j < i).copied();
+                assert_eq!(data.bit_next_one_after(i), next);
+                assert_eq!(data.bit_prev_one_before(i), prev);
+            }
+            assert_eq!(data.bit_next_one_after(usize::MAX), None);
+            assert_eq!(data.bit_prev_one_before(usize::MAX), ones.last().copied());
+        }
+    }
+
+    #[test]
+    fn test_runs() {
+        for len in [0, 1, 8, 9, 100] {
+            let data = bytes(len);
+            let bits: Vec<bool> = data.bit_iter_in::<Lsb0>().collect();
+            let runs: Vec<_> = data.bit_runs_in::<Lsb0>().collect();
+            assert_eq!(runs.iter().map(|r| r.2).sum::<usize>(), bits.len());
+            for (k, &(value, start, len)) in runs.iter().enumerate() {
+                assert!(bits[start..start + len].iter().all(|&b| b == value));
+                if k > 0 {
+                    assert_ne!(runs[k - 1].0, value);
+                    assert_eq!(runs[k - 1].1 + runs[k - 1].2, start);
+                }
+            }
+            let back: Vec<_> = data.bit_runs_in::<Lsb0>().rev().collect();
+            assert_eq!(back, runs.into_iter().rev().collect::<Vec<_>>());
+        }
+        assert_eq!([0xff; 20].bit_runs().collect::<Vec<_>>(), [(true, 0, 160)]);
+    }
+}
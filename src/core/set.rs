@@ -0,0 +1,260 @@
+use super::bitwise::{BytesExtend, WORD};
+use crate::{Error, Result};
+use std::ops::Range;
+
+/// Alignment of two byte buffers in different lengths for the set operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Align {
+    /// Align the first bytes, the shorter one is extended with zeros at the end
+    Left,
+    /// Align the last bytes like big-endian numbers, the shorter one is extended with zeros at the front
+    Right,
+    /// The lengths must be the same, or `Error::LengthMismatch`
+    Exact,
+}
+
+/**
+ * Bit set operations implementation for `[u8]`
+ * Each bit is a member of the set, the buffers in different lengths are aligned by `Align`.
+ * - the in place `*_with` operations keep the length of `self`,
+ *   an `other` longer than `self` is an `Error::LengthMismatch`
+ * - the allocating operations return a buffer of the longer length
+ */
+pub trait BitSet {
+    /// Union `self | other` in place
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// let mut data = [0b0000_0011, 0b0000_0000];
+    /// assert_eq!(data.bit_union_with(&[0b1000_0000], Align::Left).unwrap(), [0b1000_0011, 0]);
+    /// assert_eq!(data.bit_union_with(&[0b1000_0000], Align::Right).unwrap(), [0b1000_0011, 0b1000_0000]);
+    /// assert!(data.bit_union_with(&[0b1000_0000], Align::Exact).is_err());
+    /// ```
+    fn bit_union_with(&mut self, other: &Self, align: Align) -> Result<&mut Self>;
+
+    /// Intersection `self & other` in place, the bytes out of `other` are cleared
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// let mut data = [0b0000_0011, 0b0000_0011];
+    /// assert_eq!(data.bit_intersect_with(&[0b0000_0001], Align::Left).unwrap(), [0b0000_0001, 0]);
+    /// ```
+    fn bit_intersect_with(&mut self, other: &Self, align: Align) -> Result<&mut Self>;
+
+    /// Difference `self & !other` in place
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// let mut data = [0b0000_0011, 0b0000_0011];
+    /// assert_eq!(data.bit_difference_with(&[0b0000_0001], Align::Right).unwrap(), [0b0000_0011, 0b0000_0010]);
+    /// ```
+    fn bit_difference_with(&mut self, other: &Self, align: Align) -> Result<&mut Self>;
+
+    /// Symmetric difference `self ^ other` in place
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// let mut data = [0b0000_0011, 0b0000_0011];
+    /// assert_eq!(data.bit_symmetric_difference_with(&[0b0000_0110, 0b0000_0110], Align::Exact).unwrap(), [0b0000_0101, 0b0000_0101]);
+    /// ```
+    fn bit_symmetric_difference_with(&mut self, other: &Self, align: Align) -> Result<&mut Self>;
+
+    /// Union `self | other` in the longer length
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// assert_eq!([0b0000_0001].bit_union(&[0, 0b0000_0010], Align::Left).unwrap(), [0b0000_0001, 0b0000_0010]);
+    /// assert_eq!([0b0000_0001].bit_union(&[0, 0b0000_0010], Align::Right).unwrap(), [0, 0b0000_0011]);
+    /// ```
+    fn bit_union(&self, other: &Self, align: Align) -> Result<Vec<u8>>;
+
+    /// Intersection `self & other` in the longer length
+    fn bit_intersection(&self, other: &Self, align: Align) -> Result<Vec<u8>>;
+
+    /// Difference `self & !other` in the longer length
+    fn bit_difference(&self, other: &Self, align: Align) -> Result<Vec<u8>>;
+
+    /// Symmetric difference `self ^ other` in the longer length
+    fn bit_symmetric_difference(&self, other: &Self, align: Align) -> Result<Vec<u8>>;
+
+    /// Check if all one bits of `self` are one in `other`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// assert_eq!([0b0000_0001].bit_is_subset(&[0, 0b0000_0011], Align::Right), Ok(true));
+    /// assert_eq!([0b0000_0001].bit_is_subset(&[0, 0b0000_0011], Align::Left), Ok(false));
+    /// ```
+    fn bit_is_subset(&self, other: &Self, align: Align) -> Result<bool>;
+
+    /// Check if no one bits of `self` are one in `other`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::{Align, BitSet};
+    /// assert_eq!([0b0000_0001].bit_is_disjoint(&[0, 0b0000_0011], Align::Left), Ok(true));
+    /// assert_eq!([0b0000_0001].bit_is_disjoint(&[0, 0b0000_0011], Align::Right), Ok(false));
+    /// ```
+    fn bit_is_disjoint(&self, other: &Self, align: Align) -> Result<bool>;
+}
+
+impl BitSet for [u8] {
+    fn bit_union_with(&mut self, other: &Self, align: Align) -> Result<&mut Self> {
+        let over = overlap(self.len(), other.len(), align)?;
+        self[over].zip_bytes(other, |a, b| a | b);
+        Ok(self)
+    }
+
+    fn bit_intersect_with(&mut self, other: &Self, align: Align) -> Result<&mut Self> {
+        let over = overlap(self.len(), other.len(), align)?;
+        self[..over.start].fill(0);
+        self[over.end..].fill(0);
+        self[over].zip_bytes(other, |a, b| a & b);
+        Ok(self)
+    }
+
+    fn bit_difference_with(&mut self, other: &Self, align: Align) -> Result<&mut Self> {
+        let over = overlap(self.len(), other.len(), align)?;
+        self[over].zip_bytes(other, |a, b| a & !b);
+        Ok(self)
+    }
+
+    fn bit_symmetric_difference_with(&mut self, other: &Self, align: Align) -> Result<&mut Self> {
+        let over = overlap(self.len(), other.len(), align)?;
+        self[over].zip_bytes(other, |a, b| a ^ b);
+        Ok(self)
+    }
+
+    fn bit_union(&self, other: &Self, align: Align) -> Result<Vec<u8>> {
+        let mut data = extended(self, other.len(), align)?;
+        data.bit_union_with(other, align)?;
+        Ok(data)
+    }
+
+    fn bit_intersection(&self, other: &Self, align: Align) -> Result<Vec<u8>> {
+        let mut data = extended(self, other.len(), align)?;
+        data.bit_intersect_with(other, align)?;
+        Ok(data)
+    }
+
+    fn bit_difference(&self, other: &Self, align: Align) -> Result<Vec<u8>> {
+        let mut data = extended(self, other.len(), align)?;
+        data.bit_difference_with(other, align)?;
+        Ok(data)
+    }
+
+    fn bit_symmetric_difference(&self, other: &Self, align: Align) -> Result<Vec<u8>> {
+        let mut data = extended(self, other.len(), align)?;
+        data.bit_symmetric_difference_with(other, align)?;
+        Ok(data)
+    }
+
+    fn bit_is_subset(&self, other: &Self, align: Align) -> Result<bool> {
+        if self.len() <= other.len() {
+            let over = overlap(other.len(), self.len(), align)?;
+            return Ok(all_words(self, &other[over], |a, b| a & !b == 0));
+        }
+        // the bytes of `self` out of `other` must be zero
+        let over = overlap(self.len(), other.len(), align)?;
+        let mut rest = self[..over.start].iter().chain(&self[over.end..]);
+        Ok(rest.all(|&b| b == 0) && all_words(&self[over], other, |a, b| a & !b == 0))
+    }
+
+    fn bit_is_disjoint(&self, other: &Self, align: Align) -> Result<bool> {
+        let (long, short) = match self.len() >= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        let over = overlap(long.len(), short.len(), align)?;
+        Ok(all_words(&long[over], short, |a, b| a & b == 0))
+    }
+}
+
+/// A copy of the data extended with zeros to the longer length, for the allocating operations
+#[inline(always)]
+fn extended(data: &[u8], len: usize, align: Align) -> Result<Vec<u8>> {
+    let len = len.max(data.len());
+    let over = overlap(len, data.len(), align)?;
+    let mut extended = vec![0; len];
+    extended[over].copy_from_slice(data);
+    Ok(extended)
+}
+
+/// The bytes of the longer buffer of `len` aligned with the shorter buffer of `short`
+#[inline(always)]
+fn overlap(len: usize, short: usize, align: Align) -> Result<Range<usize>> {
+    match align {
+        _ if short > len => Err(Error::LengthMismatch),
+        Align::Exact if short != len => Err(Error::LengthMismatch),
+        Align::Left | Align::Exact => Ok(0..short),
+        Align::Right => Ok(len - short..len),
+    }
+}
+
+/// Check a predicate on the words of two buffers of the same length
+#[inline(always)]
+fn all_words(a: &[u8], b: &[u8], f: impl Fn(u64, u64) -> bool) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+    let (wa, wb) = (a.chunks_exact(WORD), b.chunks_exact(WORD));
+    let tail = wa.remainder().iter().zip(wb.remainder());
+    let word = |w: &[u8]| u64::from_ne_bytes(w.try_into().unwrap());
+    wa.zip(wb).all(|(x, y)| f(word(x), word(y)))
+        && tail.into_iter().all(|(&x, &y)| f(x as u64, y as u64))
+}
+
+#[cfg(test)]
+mod test_set {
+    use super::*;
+    use crate::core::BitSearch;
+    use crate::test_util::bytes;
+
+    /// The one bit positions of a buffer in the frame of `len` bytes
+    fn members(data: &[u8], len: usize, align: Align) -> Vec<usize> {
+        let shift = match align {
+            Align::Right => (len - data.len()) * 8,
+            _ => 0,
+        };
+        data.bit_ones().map(|i| i + shift).collect()
+    }
+
+    #[test]
+    fn test_set_ops() {
+        for (la, lb) in [(0, 0), (3, 3), (17, 17), (19, 5), (5, 19), (24, 9)] {
+            let (a, b) = (bytes(la, 1), bytes(lb, 7));
+            for align in [Align::Left, Align::Right] {
+                let len = la.max(lb);
+                let (ma, mb) = (members(&a, len, align), members(&b, len, align));
+                let check = |result: Vec<u8>, f: &dyn Fn(bool, bool) -> bool| {
+                    let expect: Vec<usize> = (0..len * 8)
+                        .filter(|i| f(ma.contains(i), mb.contains(i)))
+                        .collect();
+                    assert_eq!(result.bit_ones().collect::<Vec<_>>(), expect);
+                };
+                check(a.bit_union(&b, align).unwrap(), &|x, y| x | y);
+                check(a.bit_intersection(&b, align).unwrap(), &|x, y| x & y);
+                check(a.bit_difference(&b, align).unwrap(), &|x, y| x & !y);
+                check(a.bit_symmetric_difference(&b, align).unwrap(), &|x, y| {
+                    x ^ y
+                });
+
+                let subset = ma.iter().all(|i| mb.contains(i));
+                let disjoint = ma.iter().all(|i| !mb.contains(i));
+                assert_eq!(a.bit_is_subset(&b, align), Ok(subset));
+                assert_eq!(a.bit_is_disjoint(&b, align), Ok(disjoint));
+                let union = a.bit_union(&b, align).unwrap();
+                assert_eq!(a.bit_is_subset(&union, align), Ok(true));
+                assert_eq!(b.bit_is_subset(&union, align), Ok(true));
+                let diff = a.bit_difference(&b, align).unwrap();
+                assert_eq!(diff.bit_is_disjoint(&b, align), Ok(true));
+
+                // in place keeps the length of `self`
+                let mut data = a.clone();
+                match data.bit_union_with(&b, align) {
+                    Ok(data) => assert_eq!(data, a.bit_union(&b, align).unwrap()),
+                    Err(e) => assert!(lb > la && e == Error::LengthMismatch),
+                }
+            }
+            let exact = la == lb;
+            assert_eq!(a.bit_union(&b, Align::Exact).is_ok(), exact);
+            assert_eq!(a.bit_is_subset(&b, Align::Exact).is_ok(), exact);
+        }
+    }
+}
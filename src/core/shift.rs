@@ -0,0 +1,290 @@
+use super::Bitwise;
+use super::bitwise::BytesExtend;
+use crate::assert_range;
+use std::ops::Range;
+
+/**
+ * Rotations, funnel shifts and shifts with the shifted-out bits for `[u8]`
+ * The bits are in big-endian order like `bit_shl` and `bit_shr`, the bit 0 is the highest bit.
+ */
+pub trait BitShift {
+    /// Rotate the bits to the left by `n % bits`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b1100_0000, 0b0000_0001].bit_rotl(3), [0b0000_0000, 0b0000_1110]);
+    /// assert_eq!([0b1100_0000, 0b0000_0001].bit_rotl(16), [0b1100_0000, 0b0000_0001]);
+    /// ```
+    fn bit_rotl(&mut self, n: usize) -> &mut Self;
+
+    /// Rotate the bits to the right by `n % bits`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b1100_0000, 0b0000_0001].bit_rotr(3), [0b0011_1000, 0b0000_0000]);
+    /// ```
+    fn bit_rotr(&mut self, n: usize) -> &mut Self;
+
+    /// Rotate the bits in the range to the left by `n % range.len()`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b0100_0000, 0b1111_1111].bit_rotl_range(1..5, 1), [0b0000_1000, 0b1111_1111]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits
+    fn bit_rotl_range(&mut self, range: Range<usize>, n: usize) -> &mut Self;
+
+    /// Rotate the bits in the range to the right by `n % range.len()`
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b0000_1000, 0b1111_1111].bit_rotr_range(1..5, 1), [0b0100_0000, 0b1111_1111]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits
+    fn bit_rotr_range(&mut self, range: Range<usize>, n: usize) -> &mut Self;
+
+    /// Funnel shift to the left, the vacated right bits are filled with the first `n` bits of `other`,
+    /// the same as the first bits of `self ++ other` shifted to the left
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b0000_1111].bit_funnel_shl(&[0b1010_0000, 0], 3), [0b0111_1101]);
+    /// ```
+    /// # Panics
+    /// - if `n` is greater than the bits of `other`
+    fn bit_funnel_shl(&mut self, other: &Self, n: usize) -> &mut Self;
+
+    /// Funnel shift to the right, the vacated left bits are filled with the last `n` bits of `other`,
+    /// the same as the last bits of `other ++ self` shifted to the right
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// assert_eq!([0b1111_0000].bit_funnel_shr(&[0, 0b0000_0101], 3), [0b1011_1110]);
+    /// ```
+    /// # Panics
+    /// - if `n` is greater than the bits of `other`
+    fn bit_funnel_shr(&mut self, other: &Self, n: usize) -> &mut Self;
+
+    /// Shift the bits to the left, returns the shifted-out bits as a big-endian number
+    /// # Returns
+    /// - the first `n` bits in `n.div_ceil(8)` bytes
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// let mut data = [0b1011_0000, 0b0000_0001];
+    /// assert_eq!(data.bit_shl_out(3), [0b0000_0101]);
+    /// assert_eq!(data, [0b1000_0000, 0b0000_1000]);
+    /// ```
+    /// # Panics
+    /// - if `n` is greater than the bits
+    fn bit_shl_out(&mut self, n: usize) -> Vec<u8>;
+
+    /// Shift the bits to the right, returns the shifted-out bits as a big-endian number
+    /// # Returns
+    /// - the last `n` bits in `n.div_ceil(8)` bytes
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitShift;
+    /// let mut data = [0b1000_0000, 0b0000_0101];
+    /// assert_eq!(data.bit_shr_out(3), [0b0000_0101]);
+    /// assert_eq!(data, [0b0001_0000, 0b0000_0000]);
+    /// ```
+    /// # Panics
+    /// - if `n` is greater than the bits
+    fn bit_shr_out(&mut self, n: usize) -> Vec<u8>;
+}
+
+impl BitShift for [u8] {
+    fn bit_rotl(&mut self, n: usize) -> &mut Self {
+        if self.is_empty() {
+            return self;
+        }
+        let n = n % (self.len() * 8);
+        self.rotate_left(n / 8);
+        let m = n % 8;
+        if m != 0 {
+            let first = self[0];
+            self.shl_bits(m);
+            *self.last_mut().unwrap() |= first >> (8 - m);
+        }
+        self
+    }
+
+    fn bit_rotr(&mut self, n: usize) -> &mut Self {
+        if self.is_empty() {
+            return self;
+        }
+        let n = n % (self.len() * 8);
+        self.rotate_right(n / 8);
+        let m = n % 8;
+        if m != 0 {
+            let last = self[self.len() - 1];
+            self.shr_bits(m);
+            self[0] |= last << (8 - m);
+        }
+        self
+    }
+
+    fn bit_rotl_range(&mut self, range: Range<usize>, n: usize) -> &mut Self {
+        let (start, end, bits) = (range.start, range.end, self.len() * 8);
+        assert_range!(end, start, bits, "bit range");
+        let len = range.len();
+        let n = n % len.max(1);
+        if n == 0 {
+            return self;
+        }
+        // put the shorter side aside, move the longer side over it, then copy it back
+        if n <= len - n {
+            let mut head = vec![0; n.div_ceil(8)];
+            head.bit_copy(0, self, start, n);
+            self.bit_copy_within(start + n..end, start);
+            self.bit_copy(end - n, &head, 0, n)
+        } else {
+            let mut tail = vec![0; (len - n).div_ceil(8)];
+            tail.bit_copy(0, self, start + n, len - n);
+            self.bit_copy_within(start..start + n, end - n);
+            self.bit_copy(start, &tail, 0, len - n)
+        }
+    }
+
+    fn bit_rotr_range(&mut self, range: Range<usize>, n: usize) -> &mut Self {
+        let len = range.len();
+        match len {
+            0 => self.bit_rotl_range(range, 0),
+            _ => self.bit_rotl_range(range, len - n % len),
+        }
+    }
+
+    fn bit_funnel_shl(&mut self, other: &Self, n: usize) -> &mut Self {
+        let bits = other.len() * 8;
+        assert_range!(n, 0, bits, "bit_funnel_shl");
+        self.bit_shl(n);
+        let shifted = other.to_vec().bit_shl_out(n);
+        let tail = self.iter_mut().rev().zip(shifted.iter().rev());
+        tail.for_each(|(a, b)| *a |= b);
+        self
+    }
+
+    fn bit_funnel_shr(&mut self, other: &Self, n: usize) -> &mut Self {
+        let bits = other.len() * 8;
+        assert_range!(n, 0, bits, "bit_funnel_shr");
+        self.bit_shr(n);
+        // the last `n` bits of `other` aligned to the left
+        let (bytes, pad) = (n.div_ceil(8), n.div_ceil(8) * 8 - n);
+        let mut shifted = other[other.len() - bytes..].to_vec();
+        shifted.bit_shl(pad);
+        self.iter_mut().zip(&shifted).for_each(|(a, b)| *a |= b);
+        self
+    }
+
+    fn bit_shl_out(&mut self, n: usize) -> Vec<u8> {
+        let bits = self.len() * 8;
+        assert_range!(n, 0, bits, "bit_shl_out");
+        let (bytes, pad) = (n.div_ceil(8), n.div_ceil(8) * 8 - n);
+        let mut out = self[..bytes].to_vec();
+        out.bit_shr(pad);
+        self.bit_shl(n);
+        out
+    }
+
+    fn bit_shr_out(&mut self, n: usize) -> Vec<u8> {
+        let bits = self.len() * 8;
+        assert_range!(n, 0, bits, "bit_shr_out");
+        let (bytes, pad) = (n.div_ceil(8), n.div_ceil(8) * 8 - n);
+        let mut out = self[self.len() - bytes..].to_vec();
+        if let Some(first) = out.first_mut() {
+            *first &= 0xff >> pad;
+        }
+        self.bit_shr(n);
+        out
+    }
+}
+
+#[cfg(test)]
+mod test_shift {
+    use super::*;
+    use crate::core::{FromBits, Msb0};
+    use crate::test_util::{self, bytes};
+
+    fn bits(data: &[u8]) -> Vec<bool> {
+        test_util::bits::<Msb0>(data)
+    }
+
+    #[test]
+    fn test_rotate() {
+        for len in [1, 2, 7, 9, 20] {
+            let data = bytes(len, 0);
+            for n in [0, 1, 7, 8, 9, 15, 64, len * 8 - 1, len * 8 + 3] {
+                let mut expect = bits(&data);
+                expect.rotate_left(n % (len * 8));
+                assert_eq!(bits(data.clone().bit_rotl(n)), expect);
+                expect = bits(&data);
+                expect.rotate_right(n % (len * 8));
+                assert_eq!(bits(data.clone().bit_rotr(n)), expect);
+
+                let mut rotated = data.clone();
+                rotated.bit_rotl(n).bit_rotr(n);
+                assert_eq!(rotated, data);
+            }
+            for range in [0..0, 0..1, 3..5, 5..len * 8, 1..len * 8 - 1] {
+                for n in [1, 3, 9, 70] {
+                    let mut expect = bits(&data);
+                    if !range.is_empty() {
+                        expect[range.clone()].rotate_left(n % range.len());
+                    }
+                    let mut rotated = data.clone();
+                    assert_eq!(bits(rotated.bit_rotl_range(range.clone(), n)), expect);
+                    assert_eq!(rotated.bit_rotr_range(range.clone(), n), data);
+                }
+            }
+        }
+        assert_eq!([0u8; 0].bit_rotl(3), []);
+    }
+
+    #[test]
+    fn test_funnel_shift() {
+        let (a, b) = (bytes(5, 0), bytes(3, 0));
+        for n in [0, 1, 8, 13, 24] {
+            let joined = bits(&a).into_iter().chain(bits(&b)).collect::<Vec<_>>();
+            let expect = Vec::<u8>::from_bits(joined[n..n + 40].iter().copied());
+            assert_eq!(a.clone().bit_funnel_shl(&b, n), expect);
+
+            let joined = bits(&b).into_iter().chain(bits(&a)).collect::<Vec<_>>();
+            let expect = Vec::<u8>::from_bits(joined[24 - n..64 - n].iter().copied());
+            assert_eq!(a.clone().bit_funnel_shr(&b, n), expect);
+        }
+        // more bits than `self`
+        assert_eq!([0_u8].bit_funnel_shl(&[0x12, 0x34], 12), [0x23]);
+        assert_eq!([0_u8].bit_funnel_shr(&[0x12, 0x34], 12), [0x23]);
+    }
+
+    #[test]
+    fn test_shift_out() {
+        let data = bytes(6, 0);
+        for n in [0, 1, 8, 11, 48] {
+            let mut shifted = data.clone();
+            let out = shifted.bit_shl_out(n);
+            let mut expect = data.clone();
+            expect.bit_shl(n);
+            assert_eq!(shifted, expect);
+            assert_eq!(bits(&out)[out.len() * 8 - n..], bits(&data)[..n]);
+            assert_eq!(out.len(), n.div_ceil(8));
+
+            let mut shifted = data.clone();
+            let out = shifted.bit_shr_out(n);
+            let mut expect = data.clone();
+            expect.bit_shr(n);
+            assert_eq!(shifted, expect);
+            assert_eq!(bits(&out)[out.len() * 8 - n..], bits(&data)[48 - n..]);
+            assert!(bits(&out)[..out.len() * 8 - n].iter().all(|&b| !b));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_shift_out_bounds() {
+        [0_u8; 2].bit_shl_out(17);
+    }
+}
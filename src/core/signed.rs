@@ -0,0 +1,348 @@
+use super::BitArith;
+use super::Bitwise;
+use std::cmp::Ordering;
+
+/**
+ * Signed (two's-complement) arithmetic operations implementation for `[u8]`
+ */
+pub trait BitSigned {
+    type Other: ?Sized;
+
+    /// Check if the sign bit is set
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// assert_eq!([0b1000_0000, 0b0000_0000].bit_is_negative(), true);
+    /// assert_eq!([0b0111_1111, 0b1111_1111].bit_is_negative(), false);
+    /// ```
+    fn bit_is_negative(&self) -> bool;
+
+    /// Two's-complement negation
+    /// # Returns
+    /// - `true` if the value is the minimum value, which has no positive counterpart
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut a = (-300_i16).to_be_bytes();
+    /// assert_eq!(a.bit_neg(), false);
+    /// assert_eq!(a, 300_i16.to_be_bytes());
+    /// ```
+    fn bit_neg(&mut self) -> bool;
+
+    /// Sign extend to `n` bytes
+    /// # Panics
+    /// - if `n` is less than the length and the value does not fit in `n` bytes
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// assert_eq!((-2_i8).to_be_bytes().bit_sign_extend(4), (-2_i32).to_be_bytes());
+    /// assert_eq!(2_i8.to_be_bytes().bit_sign_extend(4), 2_i32.to_be_bytes());
+    /// assert_eq!((-2_i32).to_be_bytes().bit_sign_extend(2), (-2_i16).to_be_bytes());
+    /// ```
+    fn bit_sign_extend(&self, n: usize) -> Vec<u8>;
+
+    /// Signed comparison for big-endian
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// # use std::cmp::Ordering;
+    /// assert_eq!([0b1111_1111].bit_be_signed_cmp(&[0b0000_0001]), Ordering::Less);
+    /// assert_eq!([0b1111_1111].bit_be_signed_cmp(&[0b1111_1111, 0b1111_1111]), Ordering::Equal);
+    /// assert_eq!([0b0000_0001].bit_be_signed_cmp(&[0b1000_0000, 0b0000_0000]), Ordering::Greater);
+    /// ```
+    fn bit_be_signed_cmp(&self, other: &Self) -> Ordering;
+
+    /// Signed arithmetic operator `+=` for big-endian
+    /// # Returns
+    /// - `true` if the result does not fit in the length of `self`
+    /// # Example
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut a = (-300_i16).to_be_bytes();
+    /// assert_eq!(a.bit_be_signed_add(&[0b0000_0001]), false);
+    /// assert_eq!(a, (-299_i16).to_be_bytes());
+    /// assert_eq!(a.bit_be_signed_add(&[0b1111_1111]), false);
+    /// assert_eq!(a, (-300_i16).to_be_bytes());
+    /// ```
+    fn bit_be_signed_add(&mut self, other: &Self::Other) -> bool;
+
+    /// Signed arithmetic operator `-=` for big-endian
+    /// # Returns
+    /// - `true` if the result does not fit in the length of `self`
+    /// # Example
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut a = 1_i16.to_be_bytes();
+    /// assert_eq!(a.bit_be_signed_sub(&[0b0000_0010]), false);
+    /// assert_eq!(a, (-1_i16).to_be_bytes());
+    /// ```
+    fn bit_be_signed_sub(&mut self, other: &Self::Other) -> bool;
+
+    /// Signed arithmetic operator `*=` for big-endian
+    /// # Returns
+    /// - `true` if the result does not fit in the length of `self`
+    /// # Example
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut a = (-300_i16).to_be_bytes();
+    /// assert_eq!(a.bit_be_signed_mul(&(-3_i8).to_be_bytes()), false);
+    /// assert_eq!(a, 900_i16.to_be_bytes());
+    /// ```
+    fn bit_be_signed_mul(&mut self, other: &Self::Other) -> bool;
+
+    /// Signed arithmetic operator `/=` for big-endian, rounding toward zero
+    /// # Returns
+    /// - `true` on division by zero, `self` is unchanged
+    /// - `true` if the minimum value is divided by `-1`, `self` wraps to the minimum value
+    /// # Example
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut a = (-301_i16).to_be_bytes();
+    /// assert_eq!(a.bit_be_signed_div(&3_i8.to_be_bytes()), false);
+    /// assert_eq!(a, (-100_i16).to_be_bytes());
+    /// ```
+    fn bit_be_signed_div(&mut self, other: &Self::Other) -> bool;
+
+    /// Arithmetic shift right, filling the leftmost bits with the sign bit
+    /// # Returns
+    /// - `true` if the rightmost `1` bits are overflowed
+    /// # Examples
+    /// ```
+    /// # use xbits::core::BitSigned;
+    /// let mut data = [0b1111_0000, 0b0000_0000];
+    /// assert_eq!(data.bit_sar(4), false);
+    /// assert_eq!(data, [0b1111_1111, 0b0000_0000]);
+    /// ```
+    fn bit_sar(&mut self, n: usize) -> bool;
+}
+
+impl BitSigned for [u8] {
+    type Other = Self;
+
+    fn bit_is_negative(&self) -> bool {
+        self.first().is_some_and(|&b| b & 0x80 != 0)
+    }
+
+    fn bit_neg(&mut self) -> bool {
+        let negative = self.bit_is_negative();
+        self.bit_not();
+        self.bit_be_add(&[1]);
+        negative && self.bit_is_negative()
+    }
+
+    fn bit_sign_extend(&self, n: usize) -> Vec<u8> {
+        let fill = if self.bit_is_negative() { 0xff } else { 0 };
+        let mut data = vec![fill; n];
+        if self.len() <= n {
+            data[n - self.len()..].copy_from_slice(self);
+        } else {
+            data.copy_from_slice(&self[self.len() - n..]);
+            assert!(
+                self[..self.len() - n].iter().all(|&b| b == fill)
+                    && data.bit_is_negative() == self.bit_is_negative(),
+                "[xbits] bit_sign_extend value not fit in `{n}` bytes"
+            );
+        }
+        data
+    }
+
+    fn bit_be_signed_cmp(&self, other: &Self) -> Ordering {
+        match (self.bit_is_negative(), other.bit_is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => {
+                let max_len = std::cmp::max(self.len(), other.len());
+                self.bit_sign_extend(max_len)
+                    .cmp(&other.bit_sign_extend(max_len))
+            }
+        }
+    }
+
+    fn bit_be_signed_add(&mut self, other: &Self) -> bool {
+        let (b, fit) = other.sign_fit(self.len());
+        let (neg_a, neg_b) = (self.bit_is_negative(), b.bit_is_negative());
+        self.bit_be_add(&b);
+        !fit || (neg_a == neg_b && self.bit_is_negative() != neg_a)
+    }
+
+    fn bit_be_signed_sub(&mut self, other: &Self) -> bool {
+        let (b, fit) = other.sign_fit(self.len());
+        let (neg_a, neg_b) = (self.bit_is_negative(), b.bit_is_negative());
+        self.bit_be_sub(&b);
+        !fit || (neg_a != neg_b && self.bit_is_negative() != neg_a)
+    }
+
+    fn bit_be_signed_mul(&mut self, other: &Self) -> bool {
+        let n = self.len();
+        let negative = self.bit_is_negative() != other.bit_is_negative();
+
+        // Multiply magnitudes in a buffer wide enough to never overflow
+        let mut product = vec![0; n + other.len()];
+        product[other.len()..].copy_from_slice(&self.magnitude());
+        product.bit_be_mul(&other.magnitude());
+        let negative = negative && !product.bit_all_zero();
+
+        let overflow = !product.magnitude_fit(n, negative);
+        self.copy_from_slice(&product[other.len()..]);
+        if negative {
+            self.bit_neg();
+        }
+        overflow
+    }
+
+    fn bit_be_signed_div(&mut self, other: &Self) -> bool {
+        if other.bit_all_zero() {
+            return true; // Division by zero, return overflow
+        }
+        let negative = self.bit_is_negative() != other.bit_is_negative();
+
+        let mut quotient = self.magnitude();
+        quotient.bit_be_div(&other.magnitude());
+        let negative = negative && !quotient.bit_all_zero();
+
+        let overflow = !quotient.magnitude_fit(self.len(), negative);
+        self.copy_from_slice(&quotient);
+        if negative {
+            self.bit_neg();
+        }
+        overflow
+    }
+
+    fn bit_sar(&mut self, n: usize) -> bool {
+        let negative = self.bit_is_negative();
+        let overflow = self.bit_shr(n);
+        if negative {
+            let n = n.min(self.len() * 8);
+            self[..n / 8].fill(0xff);
+            if !n.is_multiple_of(8) {
+                self[n / 8] |= 0xff << (8 - n % 8);
+            }
+        }
+        overflow
+    }
+}
+
+trait SignedExtend {
+    /// Absolute value as unsigned bytes of the same length
+    fn magnitude(&self) -> Vec<u8>;
+
+    /// Check if an unsigned magnitude fits in `n` signed bytes
+    fn magnitude_fit(&self, n: usize, negative: bool) -> bool;
+
+    /// Sign extend or truncate to `n` bytes, with a flag whether the value is kept
+    fn sign_fit(&self, n: usize) -> (Vec<u8>, bool);
+}
+
+impl SignedExtend for [u8] {
+    fn magnitude(&self) -> Vec<u8> {
+        let mut data = self.to_vec();
+        if data.bit_is_negative() {
+            data.bit_neg();
+        }
+        data
+    }
+
+    fn magnitude_fit(&self, n: usize, negative: bool) -> bool {
+        let (high, low) = self.split_at(self.len().saturating_sub(n));
+        if !high.bit_all_zero() {
+            return false;
+        }
+        match low.first() {
+            _ if low.len() < n => true,
+            None => true,
+            Some(&b) if b & 0x80 == 0 => true,
+            // only the minimum value has the sign bit set in its magnitude
+            Some(&b) => negative && b == 0x80 && low[1..].bit_all_zero(),
+        }
+    }
+
+    fn sign_fit(&self, n: usize) -> (Vec<u8>, bool) {
+        if self.len() <= n {
+            return (self.bit_sign_extend(n), true);
+        }
+        let data = self[self.len() - n..].to_vec();
+        let fill = if self.bit_is_negative() { 0xff } else { 0 };
+        let fit = self[..self.len() - n].iter().all(|&b| b == fill)
+            && data.bit_is_negative() == self.bit_is_negative();
+        (data, fit)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod test_signed {
+    use super::*;
+
+    #[test]
+    fn test_signed_cmp() {
+        assert_eq!([0xff].bit_be_signed_cmp(&[0x01]), Ordering::Less);
+        assert_eq!([0x80, 0x00].bit_be_signed_cmp(&[0x80]), Ordering::Less);
+        assert_eq!([0xff, 0x80].bit_be_signed_cmp(&[0x80]), Ordering::Equal);
+        assert_eq!([0x00, 0x80].bit_be_signed_cmp(&[0x7f]), Ordering::Greater);
+        assert_eq!([].bit_be_signed_cmp(&[0x00]), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_signed_add_sub() {
+        for (a, b) in [
+            (i16::MAX, 1_i8),
+            (i16::MIN, -1),
+            (-300, 127),
+            (300, -128),
+            (0, 0),
+        ] {
+            let mut x = a.to_be_bytes();
+            assert_eq!(
+                x.bit_be_signed_add(&b.to_be_bytes()),
+                a.checked_add(b as i16).is_none()
+            );
+            assert_eq!(x, a.wrapping_add(b as i16).to_be_bytes());
+
+            let mut x = a.to_be_bytes();
+            assert_eq!(
+                x.bit_be_signed_sub(&b.to_be_bytes()),
+                a.checked_sub(b as i16).is_none()
+            );
+            assert_eq!(x, a.wrapping_sub(b as i16).to_be_bytes());
+        }
+
+        // other longer than self
+        let mut x = [0x01];
+        assert_eq!(x.bit_be_signed_add(&(-2_i32).to_be_bytes()), false);
+        assert_eq!(x, (-1_i8).to_be_bytes());
+        assert_eq!(x.bit_be_signed_add(&256_i32.to_be_bytes()), true);
+    }
+
+    #[test]
+    fn test_signed_mul_div() {
+        for a in [i16::MIN, -301, -1, 0, 1, 255, i16::MAX] {
+            for b in [i8::MIN, -3, -1, 1, 2, i8::MAX] {
+                let mut x = a.to_be_bytes();
+                let overflow = x.bit_be_signed_mul(&b.to_be_bytes());
+                assert_eq!(overflow, a.checked_mul(b as i16).is_none(), "{a} * {b}");
+                assert_eq!(x, a.wrapping_mul(b as i16).to_be_bytes(), "{a} * {b}");
+
+                let mut x = a.to_be_bytes();
+                let overflow = x.bit_be_signed_div(&b.to_be_bytes());
+                assert_eq!(overflow, a.checked_div(b as i16).is_none(), "{a} / {b}");
+                assert_eq!(x, a.wrapping_div(b as i16).to_be_bytes(), "{a} / {b}");
+            }
+        }
+        let mut x = [0x12];
+        assert_eq!(x.bit_be_signed_div(&[0]), true);
+        assert_eq!(x, [0x12]);
+    }
+
+    #[test]
+    fn test_neg_sar() {
+        let mut x = i16::MIN.to_be_bytes();
+        assert_eq!(x.bit_neg(), true);
+        assert_eq!(x, i16::MIN.to_be_bytes());
+
+        for n in [0, 1, 7, 8, 9, 15, 16, 20] {
+            let mut x = (-12345_i16).to_be_bytes();
+            x.bit_sar(n);
+            assert_eq!(x, ((-12345_i16) >> n.min(15)).to_be_bytes(), "sar {n}");
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::{BitOrder, Bitwise, Msb0, read_field};
+use crate::{BitsRef, Error, Result, assert_range};
+use std::marker::PhantomData;
+
+/// A reading position over in-memory bits in the bit order `O`.
+/// Unlike `BitReader` it reads from any bit position of a slice without copying,
+/// a failed read does not move the position.
+/// ```
+/// # use xbits::{AsBits, BitCursor};
+/// let data = [0b1011_0000];
+/// let mut cursor = BitCursor::new(data.as_bits());
+/// assert_eq!(cursor.read_bit().unwrap(), true);
+/// assert_eq!(cursor.read_bits(3).unwrap(), 0b011);
+/// assert_eq!(cursor.position(), 4);
+/// assert!(cursor.read_bits(5).is_err());
+/// assert_eq!(cursor.remaining(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitCursor<'a, O: BitOrder = Msb0> {
+    data: &'a [u8],
+    len: usize,      // number of readable bits
+    position: usize, // number of bits read
+    order: PhantomData<O>,
+}
+
+impl<'a, O: BitOrder> BitCursor<'a, O> {
+    /// Create a cursor at the first bit
+    #[inline(always)]
+    pub fn new(bits: BitsRef<'a, O>) -> Self {
+        Self::with_len(bits.0, bits.len())
+    }
+
+    /// Create a cursor over the first `len` bits of the data
+    #[inline(always)]
+    pub(crate) fn with_len(data: &'a [u8], len: usize) -> Self {
+        debug_assert!(len <= data.len() * 8);
+        BitCursor {
+            data,
+            len,
+            position: 0,
+            order: PhantomData,
+        }
+    }
+
+    /// Returns the number of readable bits
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no readable bits
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bits read
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move to the bit `position`
+    /// # Panics
+    /// - if `position > len`
+    #[inline(always)]
+    pub fn set_position(&mut self, position: usize) {
+        let len = self.len;
+        assert_range!(position, 0, len, "set_position");
+        self.position = position;
+    }
+
+    /// Returns the number of bits not read yet
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Read a single bit
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if all bits are read
+    #[inline(always)]
+    pub fn read_bit(&mut self) -> Result<bool> {
+        if self.position >= self.len {
+            return Err(Error::UnexpectedEnd);
+        }
+        self.position += 1;
+        Ok(self.data.bit_get_in::<O>(self.position - 1))
+    }
+
+    /// Read `n` bits as an unsigned value, the first bit is the most significant bit
+    /// # Parameters
+    /// - `n`: the number of bits to read, 0 <= n <= 64
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if less than `n` bits remain, no bits are read
+    pub fn read_bits(&mut self, n: usize) -> Result<u64> {
+        assert_range!(n, 0, 64, "read_bits");
+        if n > self.remaining() {
+            return Err(Error::UnexpectedEnd);
+        }
+        let start = self.position;
+        self.position += n;
+        let value = read_field::<O>(self.data, start, n);
+        // the fields of `Lsb0` have their first bit lowest
+        if O::mask(0) == 1 {
+            return Ok(value.reverse_bits().checked_shr(64 - n as u32).unwrap_or(0));
+        }
+        Ok(value)
+    }
+
+    /// Skip `n` bits
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if less than `n` bits remain, no bits are skipped
+    pub fn skip_bits(&mut self, n: usize) -> Result<()> {
+        if n > self.remaining() {
+            return Err(Error::UnexpectedEnd);
+        }
+        self.position += n;
+        Ok(())
+    }
+
+    /// Run a read, the position is restored if it fails
+    pub(crate) fn attempt<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let position = self.position;
+        let result = read(self);
+        if result.is_err() {
+            self.position = position;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{bytes, read_bits};
+    use crate::{AsBits, Bits, Lsb0};
+
+    fn check_order<O: BitOrder>() {
+        let data = bytes(24, 3);
+        for n in [1, 7, 13, 33, 57, 64] {
+            let mut cursor = BitCursor::new(BitsRef::<O>::new(&data));
+            cursor.skip_bits(5).unwrap();
+            while cursor.remaining() >= n {
+                let expect = read_bits::<O>(&data, cursor.position(), n, true);
+                assert_eq!(cursor.read_bits(n), Ok(expect));
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_bits() {
+        check_order::<Msb0>();
+        check_order::<Lsb0>();
+    }
+
+    #[test]
+    fn test_cursor() {
+        let data = [0b1010_0001, 0xff, 0x0f];
+        let mut cursor = BitCursor::new(data.as_bits());
+        assert_eq!(cursor.len(), 24);
+        assert_eq!(cursor.read_bits(0), Ok(0));
+        assert_eq!(cursor.read_bits(3), Ok(0b101));
+        assert_eq!(cursor.read_bits(9), Ok(0b0_0001_1111));
+        assert_eq!(cursor.read_bits(13), Err(Error::UnexpectedEnd));
+        assert_eq!(cursor.position(), 12);
+        cursor.skip_bits(8).unwrap();
+        assert_eq!(cursor.read_bits(4), Ok(0xf));
+        assert_eq!(cursor.read_bit(), Err(Error::UnexpectedEnd));
+
+        cursor.set_position(0);
+        assert_eq!(cursor.read_bits(64), Err(Error::UnexpectedEnd));
+        assert_eq!(
+            cursor.attempt(|c| c.read_bits(20).and(c.read_bits(5))),
+            Err(Error::UnexpectedEnd)
+        );
+        assert_eq!(cursor.position(), 0);
+
+        let mut cursor = BitCursor::new(data.as_bits_in::<Lsb0>());
+        assert_eq!(cursor.read_bits(3), Ok(0b100));
+
+        let bits: Bits = [true, false, true].into_iter().collect();
+        let mut cursor = bits.cursor();
+        assert_eq!(cursor.len(), 3);
+        assert_eq!(cursor.read_bits(3), Ok(0b101));
+        assert_eq!(cursor.read_bit(), Err(Error::UnexpectedEnd));
+    }
+}
@@ -0,0 +1,303 @@
+/**
+ * Elias-Fano encoding of a sorted sequence of `n` integers below the universe `u`.
+ * Each value is split at `l = log2(u / n)` bits:
+ * - the upper parts are counted in unary: the `i`-th value sets the bit `(value >> l) + i`
+ * - the lower parts are packed in `l` bits each, the same layout `bit_chunks` reads
+ *
+ * It takes about `n * (2 + log2(u / n))` bits, `get` is a `select1` on the upper bits
+ * and `next_geq` is a `select0` to the bucket of the target.
+ * The position of every 256th one is sampled, `get` jumps to the sample before the value
+ * and scans less than 256 ones, skipping the zeros a word at a time. It is O(1) time since
+ * at most about half of the upper bits are zeros. `next_geq` runs in O(log n) time
+ * by the binary search of `RankIndex::select` over the superblocks.
+ */
+use crate::core::{Positions, read_field};
+use crate::rank::RankIndex;
+use crate::varint::{CompactSize, VarInt};
+use crate::{AsBitsMut, Bits, Error, Msb0, Result};
+
+/// Ones between two samples of the upper bits
+const SAMPLE_ONES: usize = 256;
+
+/// A sorted sequence of integers in Elias-Fano encoding
+/// ```
+/// # use xbits::EliasFano;
+/// let heights = EliasFano::new(&[3, 4, 7, 13, 14, 15, 21, 43]);
+/// assert_eq!(heights.len(), 8);
+/// assert_eq!(heights.get(3), Some(13));
+/// assert_eq!(heights.next_geq(16), Some((6, 21)));
+/// assert_eq!(heights.next_geq(44), None);
+/// assert_eq!(heights.iter().collect::<Vec<_>>(), [3, 4, 7, 13, 14, 15, 21, 43]);
+///
+/// let parsed = EliasFano::from_bytes(&heights.to_bytes()).unwrap();
+/// assert_eq!(parsed, heights);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EliasFano {
+    len: usize,             // number of values
+    low_bits: usize,        // bits of each lower part
+    upper: Bits,            // upper parts in unary
+    lower: Bits,            // lower parts packed
+    index: RankIndex<Msb0>, // select index of the upper bits
+    samples: Vec<usize>,    // positions of every `SAMPLE_ONES`-th one of the upper bits
+}
+
+impl EliasFano {
+    /// Encode the sorted values
+    /// # Panics
+    /// - if the values are not sorted
+    pub fn new(values: &[u64]) -> Self {
+        assert!(values.is_sorted(), "[xbits] EliasFano values not sorted");
+        let len = values.len();
+        let universe = values.last().map_or(0, |&v| v as u128 + 1);
+        let low_bits = match universe > len as u128 {
+            true => ((universe / len as u128).ilog2() as usize).min(63),
+            false => 0,
+        };
+
+        let high_max = values.last().map_or(0, |&v| (v >> low_bits) as usize);
+        let mut upper = Bits::new(if len == 0 { 0 } else { len + high_max });
+        let mut lower = Bits::new(0);
+        for (i, &value) in values.iter().enumerate() {
+            upper.set((value >> low_bits) as usize + i, true);
+            lower.push_bits(value, low_bits);
+        }
+        Self::with_parts(len, low_bits, upper, lower)
+    }
+
+    fn with_parts(len: usize, low_bits: usize, upper: Bits, lower: Bits) -> Self {
+        let index = RankIndex::new(upper.as_bytes());
+        let samples = Positions::<Msb0>::new(upper.as_bytes(), 0..upper.len(), true)
+            .step_by(SAMPLE_ONES)
+            .collect();
+        EliasFano {
+            len,
+            low_bits,
+            upper,
+            lower,
+            index,
+            samples,
+        }
+    }
+
+    /// Returns the number of values
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no values
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the `i`-th value in O(1) time
+    /// # Returns
+    /// - `None` if `i >= len`
+    pub fn get(&self, i: usize) -> Option<u64> {
+        if i >= self.len {
+            return None;
+        }
+        // scan the ones from the sample before the `i`-th one
+        let start = self.samples[i / SAMPLE_ONES];
+        let mut ones = Positions::<Msb0>::new(self.upper.as_bytes(), start..self.upper.len(), true);
+        let position = ones.nth(i % SAMPLE_ONES)?;
+        Some(self.value(i, position))
+    }
+
+    /// Returns the index and the value of the first value not less than `x`,
+    /// in O(log n) time to the bucket of `x` and a scan of the bucket
+    /// # Returns
+    /// - `None` if all values are less than `x`
+    pub fn next_geq(&self, x: u64) -> Option<(usize, u64)> {
+        let high = usize::try_from(x >> self.low_bits).ok()?;
+        if self.is_empty() || high > self.upper.len() - self.len {
+            return None;
+        }
+        // the bucket `high` starts after the `high`-th zero, the values before it are less than `x`
+        let data = self.upper.as_bytes();
+        let start = match high {
+            0 => 0,
+            _ => self.index.select(data, high - 1, false) + 1,
+        };
+        Positions::<Msb0>::new(data, start..self.upper.len(), true)
+            .zip(start - high..)
+            .map(|(position, i)| (i, self.value(i, position)))
+            .find(|&(_, value)| value >= x)
+    }
+
+    /// Iterator the values
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        Positions::<Msb0>::new(self.upper.as_bytes(), 0..self.upper.len(), true)
+            .enumerate()
+            .map(|(i, position)| self.value(i, position))
+    }
+
+    /// The `i`-th value with its one bit at the `position` of the upper bits
+    #[inline(always)]
+    fn value(&self, i: usize, position: usize) -> u64 {
+        let low = read_field::<Msb0>(self.lower.as_bytes(), i * self.low_bits, self.low_bits);
+        ((position - i) as u64) << self.low_bits | low
+    }
+
+    /// Serialize the sequence: the `CompactSize` of `n`, a byte of `l`,
+    /// the `CompactSize` of the upper bits, the upper bytes and the lower bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CompactSize::to_vec(self.len as u64).unwrap();
+        bytes.push(self.low_bits as u8);
+        CompactSize::encode(self.upper.len() as u64, &mut bytes).unwrap();
+        bytes.extend(self.upper.as_bytes());
+        bytes.extend(self.lower.as_bytes());
+        bytes
+    }
+
+    /// Parse a serialized sequence
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the data is short
+    /// - `Error::LengthMismatch` if there are bytes left or the upper bits do not have `n` ones
+    /// - `Error::Overflow` if the values do not fit in `u64`
+    /// - `Error::NonCanonical` if the padding bits are not zeros or the upper bits do not end with a one
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (len, n) = CompactSize::decode(bytes)?;
+        let (&low_bits, bytes) = bytes[n..].split_first().ok_or(Error::UnexpectedEnd)?;
+        let (upper_len, n) = CompactSize::decode(bytes)?;
+        let (len, low_bits) = (to_usize(len)?, low_bits as usize);
+        let upper_len = to_usize(upper_len)?;
+        if low_bits > 63 || upper_len < len {
+            return Err(Error::Overflow);
+        }
+        let high_bits = usize::BITS - (upper_len - len).leading_zeros();
+        if low_bits + high_bits as usize > 64 {
+            return Err(Error::Overflow);
+        }
+
+        let lower_len = len.checked_mul(low_bits).ok_or(Error::Overflow)?;
+        let (upper_bytes, bytes) = bytes[n..]
+            .split_at_checked(upper_len.div_ceil(8))
+            .ok_or(Error::UnexpectedEnd)?;
+        let (lower_bytes, bytes) = bytes
+            .split_at_checked(lower_len.div_ceil(8))
+            .ok_or(Error::UnexpectedEnd)?;
+        if !bytes.is_empty() {
+            return Err(Error::LengthMismatch);
+        }
+
+        let (mut upper, mut lower) = (Bits::new(upper_len), Bits::new(lower_len));
+        upper.as_bits_mut().0.copy_from_slice(upper_bytes);
+        lower.as_bits_mut().0.copy_from_slice(lower_bytes);
+        let sequence = Self::with_parts(len, low_bits, upper, lower);
+
+        if sequence.index.count_ones() != len {
+            return Err(Error::LengthMismatch);
+        }
+        let upper_end = match len {
+            0 => 0,
+            _ => sequence.index.select(upper_bytes, len - 1, true) + 1,
+        };
+        let lower_pad = lower_bytes.len() * 8 - lower_len;
+        if upper_end != upper_len || read_field::<Msb0>(lower_bytes, lower_len, lower_pad) != 0 {
+            return Err(Error::NonCanonical);
+        }
+        Ok(sequence)
+    }
+}
+
+#[inline(always)]
+fn to_usize(value: u64) -> Result<usize> {
+    usize::try_from(value).map_err(|_| Error::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::BitIterator;
+
+    fn check(values: &[u64]) {
+        let sequence = EliasFano::new(values);
+        assert_eq!(sequence.len(), values.len());
+        assert_eq!(sequence.iter().collect::<Vec<_>>(), values);
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(sequence.get(i), Some(value));
+        }
+        assert_eq!(sequence.get(values.len()), None);
+        let samples: Vec<usize> = (0..values.len())
+            .step_by(SAMPLE_ONES)
+            .map(|i| sequence.index.select(sequence.upper.as_bytes(), i, true))
+            .collect();
+        assert_eq!(sequence.samples, samples);
+
+        let mut targets: Vec<u64> = values
+            .iter()
+            .flat_map(|&v| [v, v.saturating_add(1)])
+            .collect();
+        targets.extend([0, 1, u64::MAX]);
+        for x in targets {
+            let expected = values.iter().position(|&v| v >= x).map(|i| (i, values[i]));
+            assert_eq!(sequence.next_geq(x), expected, "{x}");
+        }
+        assert_eq!(EliasFano::from_bytes(&sequence.to_bytes()), Ok(sequence));
+    }
+
+    #[test]
+    fn test_elias_fano() {
+        check(&[]);
+        check(&[0]);
+        check(&[u64::MAX]);
+        check(&[0, 0, 0, 1, 1]);
+        check(&[5, 5, 9, u64::MAX - 1, u64::MAX]);
+        check(&(0..1000).map(|i| i * i * 31).collect::<Vec<_>>());
+        check(&(0..3000).map(|i| i / 3).collect::<Vec<_>>());
+        // sparse gaps between the samples
+        check(&(0..2000).map(|i| (i / 300) << 40 | i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_layout() {
+        let values: Vec<u64> = (0..10_000).map(|i| i * 1000 + i % 7).collect();
+        let sequence = EliasFano::new(&values);
+        // log2(u / n) = 9 bits of the lower parts, read by `bit_chunks`
+        assert_eq!(sequence.low_bits, 9);
+        let lows: Vec<u16> = sequence
+            .lower
+            .as_bytes()
+            .bit_chunks(9)
+            .take(values.len())
+            .collect();
+        assert!(
+            lows.iter()
+                .zip(&values)
+                .all(|(&low, &v)| low as u64 == v & 0x1ff)
+        );
+        // near n * (2 + l) bits
+        assert!(sequence.to_bytes().len() * 8 < values.len() * 12);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let bytes = EliasFano::new(&[3, 4, 7, 13]).to_bytes();
+        // l = 1, the upper parts 1, 2, 3, 6 at 1, 3, 5, 9 and the lower parts 1, 0, 1, 1
+        assert_eq!(bytes, [4, 1, 10, 0b0101_0100, 0b0100_0000, 0b1011_0000]);
+        assert_eq!(
+            EliasFano::from_bytes(&bytes[..5]),
+            Err(Error::UnexpectedEnd)
+        );
+        assert_eq!(
+            EliasFano::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+            Err(Error::LengthMismatch)
+        );
+
+        let mut invalid = bytes.clone();
+        invalid[0] = 5;
+        assert_eq!(EliasFano::from_bytes(&invalid), Err(Error::LengthMismatch));
+        let mut invalid = bytes.clone();
+        invalid[4] = 0b0010_0000; // the last one in the upper padding
+        assert_eq!(EliasFano::from_bytes(&invalid), Err(Error::NonCanonical));
+        let mut invalid = bytes.clone();
+        invalid[5] = 0b1011_1000; // a one in the lower padding
+        assert_eq!(EliasFano::from_bytes(&invalid), Err(Error::NonCanonical));
+        let mut invalid = bytes.clone();
+        invalid[1] = 64;
+        assert_eq!(EliasFano::from_bytes(&invalid), Err(Error::Overflow));
+    }
+}
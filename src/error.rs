@@ -0,0 +1,37 @@
+/// Errors of the fallible bit operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The value does not fit in the target width
+    Overflow,
+    /// The lengths of the operands do not match
+    LengthMismatch,
+    /// The data ends before the value
+    UnexpectedEnd,
+    /// The value is not encoded in the shortest form
+    NonCanonical,
+    /// The name or the value is not defined
+    Undefined,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Overflow => write!(f, "[xbits] value overflow"),
+            Error::LengthMismatch => write!(f, "[xbits] length mismatch"),
+            Error::UnexpectedEnd => write!(f, "[xbits] unexpected end of data"),
+            Error::NonCanonical => write!(f, "[xbits] non-canonical encoding"),
+            Error::Undefined => write!(f, "[xbits] undefined name or value"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for std::io::Error {
+    /// Errors of the data read from or written to a stream are `InvalidData`
+    fn from(e: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
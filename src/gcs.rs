@@ -0,0 +1,246 @@
+/**
+ * Golomb-coded sets as the compact block filters of BIP158.
+ * The items are mapped by SipHash-2-4 into `0..N * M`, sorted, and the deltas are
+ * Golomb-Rice coded with the parameter `P` into a bit stream.
+ * The serialization is the `CompactSize` of `N` followed by the bit stream, the same as Bitcoin Core.
+ */
+use crate::codes::{GolombRice, UniversalCode};
+use crate::hash::siphash24;
+use crate::varint::{CompactSize, VarInt};
+use crate::{AsBits, BitCursor, Bits, Msb0, Result};
+
+/// A Golomb-coded set filter
+/// ```
+/// # use xbits::GcsFilter;
+/// let key = [7; 16];
+/// let filter = GcsFilter::build([&b"alice"[..], b"bob"], GcsFilter::BASIC_P, GcsFilter::BASIC_M, key);
+/// assert_eq!(filter.len(), 2);
+/// assert!(filter.match_one(b"alice"));
+/// assert!(filter.match_any([&b"carol"[..], b"bob"]));
+///
+/// let bytes = filter.to_bytes();
+/// let parsed = GcsFilter::from_bytes(&bytes, GcsFilter::BASIC_P, GcsFilter::BASIC_M, key).unwrap();
+/// assert_eq!(parsed, filter);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcsFilter {
+    n: u64,        // number of the distinct items
+    p: u8,         // Golomb-Rice parameter
+    m: u64,        // inverse of the false positive rate
+    key: [u8; 16], // SipHash key
+    data: Vec<u8>, // Golomb-Rice coded deltas
+}
+
+impl GcsFilter {
+    /// The `P` of the BIP158 basic filter
+    pub const BASIC_P: u8 = 19;
+    /// The `M` of the BIP158 basic filter
+    pub const BASIC_M: u64 = 784_931;
+
+    /// Build the filter of the distinct items
+    /// # Parameters
+    /// - `p`: the Golomb-Rice parameter, 0 <= p <= 63
+    /// - `m`: the inverse of the false positive rate, N * M must fit in `u64`
+    /// - `key`: the SipHash key, the first 16 bytes of the block hash in BIP158
+    /// # Panics
+    /// - if `N * M` overflows `u64`, `N` is the number of the distinct items
+    pub fn build<I>(items: I, p: u8, m: u64, key: [u8; 16]) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut items: Vec<Vec<u8>> = items.into_iter().map(|i| i.as_ref().to_vec()).collect();
+        items.sort_unstable();
+        items.dedup();
+        assert!(
+            (items.len() as u64).checked_mul(m).is_some(),
+            "[xbits] N * M overflow"
+        );
+
+        let mut filter = GcsFilter {
+            n: items.len() as u64,
+            p,
+            m,
+            key,
+            data: vec![],
+        };
+        let mut values: Vec<u64> = items.iter().map(|item| filter.hash(item)).collect();
+        values.sort_unstable();
+
+        let (code, mut bits) = (GolombRice::new(p as usize), Bits::<Msb0>::new(0));
+        let mut last = 0;
+        for value in values {
+            code.encode_into(value - last, &mut bits).unwrap();
+            last = value;
+        }
+        filter.data = bits.as_bits().0.to_vec();
+        filter
+    }
+
+    /// Parse a serialized filter
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the data ends before `N` values
+    /// - `Error::Overflow` if `N * M` or the values do not fit in `u64`
+    /// - `Error::NonCanonical` if `N` is not in the shortest `CompactSize`
+    pub fn from_bytes(bytes: &[u8], p: u8, m: u64, key: [u8; 16]) -> Result<Self> {
+        let (n, len) = CompactSize::decode(bytes)?;
+        n.checked_mul(m).ok_or(crate::Error::Overflow)?;
+        let filter = GcsFilter {
+            n,
+            p,
+            m,
+            key,
+            data: bytes[len..].to_vec(),
+        };
+        filter.values().try_for_each(|value| value.map(|_| ()))?;
+        Ok(filter)
+    }
+
+    /// Serialize the filter
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CompactSize::to_vec(self.n).unwrap();
+        bytes.extend(&self.data);
+        bytes
+    }
+
+    /// Returns the number of the distinct items
+    #[inline(always)]
+    pub fn len(&self) -> u64 {
+        self.n
+    }
+
+    /// Returns true if there are no items
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Check if the item may be in the set, false positive in the rate of `1 / M`
+    pub fn match_one(&self, item: impl AsRef<[u8]>) -> bool {
+        let target = self.hash(item.as_ref());
+        self.values()
+            .map_while(|value| value.ok())
+            .take_while(|&value| value <= target)
+            .any(|value| value == target)
+    }
+
+    /// Check if any of the items may be in the set
+    pub fn match_any<I>(&self, items: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut targets: Vec<u64> = items.into_iter().map(|i| self.hash(i.as_ref())).collect();
+        targets.sort_unstable();
+
+        // walk the two sorted lists together
+        let mut targets = targets.into_iter().peekable();
+        for value in self.values().map_while(|value| value.ok()) {
+            while targets.next_if(|&target| target < value).is_some() {}
+            match targets.peek() {
+                None => return false,
+                Some(&target) if target == value => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Map the item into `0..N * M`, which fits in `u64` since `build` and `from_bytes` check it
+    #[inline(always)]
+    fn hash(&self, item: &[u8]) -> u64 {
+        let k0 = u64::from_le_bytes(self.key[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(self.key[8..].try_into().unwrap());
+        let range = self.n * self.m;
+        ((siphash24(k0, k1, item) as u128 * range as u128) >> 64) as u64
+    }
+
+    /// Decode the sorted values
+    fn values(&self) -> impl Iterator<Item = Result<u64>> + '_ {
+        let code = GolombRice::new(self.p as usize);
+        let mut cursor = BitCursor::new(self.data.as_bits());
+        let mut last = 0_u64;
+        (0..self.n).map(move |_| {
+            let delta = code.decode_from(&mut cursor)?;
+            last = last.checked_add(delta).ok_or(crate::Error::Overflow)?;
+            Ok(last)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    /// BIP158 basic filter of the block, the key is the block hash in the internal byte order
+    fn basic(scripts: &[&[u8]], block_hash: &str) -> GcsFilter {
+        let mut hash = hex::decode(block_hash).unwrap();
+        hash.reverse();
+        let key = hash[..16].try_into().unwrap();
+        GcsFilter::build(scripts, GcsFilter::BASIC_P, GcsFilter::BASIC_M, key)
+    }
+
+    #[test]
+    fn test_bip158_vectors() {
+        // testnet genesis block, its only output script pays to the genesis public key
+        let script = hex::decode(concat!(
+            "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb6",
+            "49f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac"
+        ))
+        .unwrap();
+        let block = "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943";
+        let filter = basic(&[&script], block);
+        assert_eq!(hex::encode(filter.to_bytes()), "019dfca8");
+        assert!(filter.match_one(&script));
+
+        // no scripts
+        let filter = basic(&[], block);
+        assert_eq!(filter.to_bytes(), [0]);
+        assert!(!filter.match_one(&script));
+        assert!(!filter.match_any([&script]));
+    }
+
+    #[test]
+    fn test_match() {
+        let key = *b"0123456789abcdef";
+        let items: Vec<Vec<u8>> = (0..500_u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let filter = GcsFilter::build(&items, 19, 784_931, key);
+        assert_eq!(filter.len(), 500);
+        assert!(items.iter().all(|item| filter.match_one(item)));
+
+        let others: Vec<Vec<u8>> = (500..10_500_u32)
+            .map(|i| i.to_be_bytes().to_vec())
+            .collect();
+        let false_positives = others.iter().filter(|item| filter.match_one(item)).count();
+        assert!(false_positives < 5);
+        assert_eq!(filter.match_any(&others), false_positives > 0);
+        assert!(filter.match_any([&others[0], &items[250]]));
+        assert!(!filter.match_any(Vec::<Vec<u8>>::new()));
+
+        // duplicates are counted once
+        let doubled = GcsFilter::build(items.iter().chain(&items), 19, 784_931, key);
+        assert_eq!(doubled, filter);
+
+        let parsed = GcsFilter::from_bytes(&filter.to_bytes(), 19, 784_931, key).unwrap();
+        assert_eq!(parsed, filter);
+        let bytes = filter.to_bytes();
+        let truncated = GcsFilter::from_bytes(&bytes[..bytes.len() - 8], 19, 784_931, key);
+        assert_eq!(truncated, Err(Error::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_range_overflow() {
+        let key = [0; 16];
+        let filter = GcsFilter::build([b"one"], 63, u64::MAX, key);
+        assert!(filter.match_one(b"one"));
+        let two = GcsFilter::from_bytes(&[2, 0], 19, u64::MAX, key);
+        assert_eq!(two, Err(Error::Overflow));
+    }
+
+    #[test]
+    #[should_panic(expected = "N * M overflow")]
+    fn test_build_overflow() {
+        GcsFilter::build([b"one", b"two"], 19, u64::MAX, [0; 16]);
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+ * Non-cryptographic hashes embedded for the probabilistic filters,
+ * so their outputs match the other implementations bit for bit.
+ */
+
+/// SipHash-2-4 of the data with the 128-bit key `(k0, k1)`
+pub(crate) fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let mut words = data.chunks_exact(8);
+    for word in &mut words {
+        let m = u64::from_le_bytes(word.try_into().unwrap());
+        v[3] ^= m;
+        (0..2).for_each(|_| sip_round(&mut v));
+        v[0] ^= m;
+    }
+    // the last word has the rest bytes and the length in its highest byte
+    let mut last = [0; 8];
+    last[..words.remainder().len()].copy_from_slice(words.remainder());
+    let m = u64::from_le_bytes(last) | (data.len() as u64) << 56;
+    v[3] ^= m;
+    (0..2).for_each(|_| sip_round(&mut v));
+    v[0] ^= m;
+
+    v[2] ^= 0xff;
+    (0..4).for_each(|_| sip_round(&mut v));
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+/// MurmurHash3 x86 32-bit of the data with the seed
+pub(crate) fn murmur3_32(seed: u32, data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut h = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        h ^= mix(u32::from_le_bytes(block.try_into().unwrap()));
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    if !blocks.remainder().is_empty() {
+        h ^= mix(blocks
+            .remainder()
+            .iter()
+            .rev()
+            .fold(0, |k, &b| k << 8 | b as u32));
+    }
+
+    // finalization mix
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ h >> 16
+}
+
+#[inline(always)]
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_siphash24() {
+        // the reference vectors, key 00..0f and message 00..(len - 1)
+        let key: Vec<u8> = (0..16).collect();
+        let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+        let message: Vec<u8> = (0..64).collect();
+        assert_eq!(siphash24(k0, k1, &message[..0]), 0x726f_db47_dd0e_0e31);
+        assert_eq!(siphash24(k0, k1, &message[..1]), 0x74f8_39c5_93dc_67fd);
+        assert_eq!(siphash24(k0, k1, &message[..8]), 0x93f5_f579_9a93_2462);
+        assert_eq!(siphash24(k0, k1, &message[..15]), 0xa129_ca61_49be_45e5);
+    }
+
+    #[test]
+    fn test_murmur3_32() {
+        // the vectors of Bitcoin Core
+        let vectors: [(u32, u32, &str); 14] = [
+            (0x0000_0000, 0x0000_0000, ""),
+            (0x6a39_6f08, 0xfba4_c795, ""),
+            (0x81f1_6f39, 0xffff_ffff, ""),
+            (0x514e_28b7, 0x0000_0000, "00"),
+            (0xea3f_0b17, 0xfba4_c795, "00"),
+            (0xfd6c_f10d, 0x0000_0000, "ff"),
+            (0x16c6_b7ab, 0x0000_0000, "0011"),
+            (0x8eb5_1c3d, 0x0000_0000, "001122"),
+            (0xb447_1bf8, 0x0000_0000, "00112233"),
+            (0xe230_1fa8, 0x0000_0000, "0011223344"),
+            (0xfc2e_4a15, 0x0000_0000, "001122334455"),
+            (0xb074_502c, 0x0000_0000, "00112233445566"),
+            (0x8034_d2a0, 0x0000_0000, "0011223344556677"),
+            (0xb469_8def, 0x0000_0000, "001122334455667788"),
+        ];
+        for (expected, seed, data) in vectors {
+            assert_eq!(
+                murmur3_32(seed, &hex::decode(data).unwrap()),
+                expected,
+                "{data}"
+            );
+        }
+    }
+}
@@ -0,0 +1,419 @@
+/**
+ * Bit layouts defined at runtime, for the formats read from configurations.
+ * A `Layout` is a list of fields packed one after another without padding,
+ * each field is an unsigned, a signed or an enumerated integer of 1 to 64 bits.
+ * - `parse` reads the fields of a bit buffer into a `Record`
+ * - `encode` packs a `Record` into bits
+ * - `dump` prints the bit range, the raw bits and the value of each field
+ *
+ * The fields are read and written by `read_uint`/`write_uint` in the bit order of the buffer,
+ * so a layout of `Msb0` fields is the same as `#[derive(BitPack)]` of the fields.
+ */
+use crate::core::{BitField, BitOrder, Bitwise, FromBits, Msb0};
+use crate::{AsBitsMut, Bits, BitsRef, Error, Result, assert_range};
+use std::fmt::Write;
+
+/// The kind of the values of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// Unsigned integers
+    Uint,
+    /// Two's complement signed integers
+    Int,
+    /// Unsigned integers with the names of the values
+    Enum(Vec<(u64, String)>),
+}
+
+/// A field of a layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    name: String,
+    width: usize,
+    kind: Kind,
+}
+
+impl Field {
+    /// An unsigned field of `width` bits, 1 <= width <= 64
+    pub fn uint(name: impl Into<String>, width: usize) -> Self {
+        Self::new(name.into(), width, Kind::Uint)
+    }
+
+    /// A signed field of `width` bits, 1 <= width <= 64
+    pub fn int(name: impl Into<String>, width: usize) -> Self {
+        Self::new(name.into(), width, Kind::Int)
+    }
+
+    /// An enumerated field of `width` bits with the names of the values, 1 <= width <= 64
+    pub fn enumerated<S: Into<String>>(
+        name: impl Into<String>,
+        width: usize,
+        names: impl IntoIterator<Item = (u64, S)>,
+    ) -> Self {
+        let names = names.into_iter().map(|(v, s)| (v, s.into())).collect();
+        Self::new(name.into(), width, Kind::Enum(names))
+    }
+
+    fn new(name: String, width: usize, kind: Kind) -> Self {
+        assert_range!(width, 1, 64, "layout field");
+        Field { name, width, kind }
+    }
+
+    /// Returns the name of the field
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the bits of the field
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the kind of the field values
+    #[inline(always)]
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+
+    /// Read the value at the bit `offset`
+    fn read<O: BitOrder>(&self, bits: &BitsRef<'_, O>, offset: usize) -> Result<Value> {
+        let value = match &self.kind {
+            Kind::Int => Value::Int(bits.read_int(offset, self.width)?),
+            Kind::Uint => Value::Uint(bits.read_uint(offset, self.width)?),
+            Kind::Enum(names) => {
+                let raw = bits.read_uint(offset, self.width)?;
+                match names.iter().find(|(v, _)| *v == raw) {
+                    Some((_, name)) => Value::Enum(name.clone()),
+                    None => Value::Uint(raw), // not named
+                }
+            }
+        };
+        Ok(value)
+    }
+
+    /// Write the value at the bit `offset`
+    fn write<O: BitOrder>(&self, data: &mut [u8], offset: usize, value: &Value) -> Result<()> {
+        let width = self.width;
+        match (&self.kind, value) {
+            (Kind::Int, &Value::Int(v)) => data.bit_write_int_in::<O>(offset, width, v),
+            (Kind::Int, &Value::Uint(v)) => {
+                let v = i64::try_from(v).map_err(|_| Error::Overflow)?;
+                data.bit_write_int_in::<O>(offset, width, v)
+            }
+            (_, &Value::Int(v)) => {
+                let v = u64::try_from(v).map_err(|_| Error::Overflow)?;
+                data.bit_write_uint_in::<O>(offset, width, v)
+            }
+            (_, &Value::Uint(v)) => data.bit_write_uint_in::<O>(offset, width, v),
+            (Kind::Enum(names), Value::Enum(name)) => {
+                let (v, _) = names
+                    .iter()
+                    .find(|(_, s)| s == name)
+                    .ok_or(Error::Undefined)?;
+                data.bit_write_uint_in::<O>(offset, width, *v)
+            }
+            (_, Value::Enum(_)) => Err(Error::Undefined),
+        }
+    }
+}
+
+/// A value of a field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// An unsigned value of a `Field::uint`
+    Uint(u64),
+    /// A signed value of a `Field::int`, in two's complement in the bits
+    Int(i64),
+    /// The name of an enumerated value
+    Enum(String),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Uint(v) => write!(f, "{v}"),
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Enum(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// The values of the fields by name, in the order of the fields
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    values: Vec<(String, Value)>,
+}
+
+impl Record {
+    /// An empty record, the values are set by `with` or `set`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of the field, returns the record
+    pub fn with(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Set the value of the field
+    pub fn set(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        match self.values.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((name, value)),
+        }
+    }
+
+    /// Returns the value of the field
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Returns the number of the values
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if there are no values
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterator the names and the values
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values.iter().map(|(n, v)| (n.as_str(), v))
+    }
+}
+
+/// A bit layout of fields
+/// ```
+/// # use xbits::AsBits;
+/// # use xbits::layout::{Field, Layout, Record, Value};
+/// let layout = Layout::new([
+///     Field::uint("version", 3),
+///     Field::enumerated("network", 1, [(0, "main"), (1, "test")]),
+///     Field::int("offset", 12),
+/// ]);
+/// assert_eq!(layout.bits(), 16);
+///
+/// let data = [0b1011_1111, 0b1111_1110];
+/// let record = layout.parse(&data.as_bits()).unwrap();
+/// assert_eq!(record.get("version"), Some(&Value::Uint(5)));
+/// assert_eq!(record.get("network"), Some(&Value::Enum("test".into())));
+/// assert_eq!(record.get("offset"), Some(&Value::Int(-2)));
+/// assert_eq!(layout.encode(&record).unwrap().as_bytes(), data);
+///
+/// print!("{}", layout.dump(&data.as_bits()).unwrap());
+/// // 10111111 11111110
+/// // 0..3   version  101           5
+/// // 3..4   network  1             test
+/// // 4..16  offset   111111111110  -2
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    /// Create a layout of the fields in order
+    /// # Panics
+    /// - if the names of the fields are not unique
+    pub fn new(fields: impl IntoIterator<Item = Field>) -> Self {
+        let fields: Vec<Field> = fields.into_iter().collect();
+        for (i, field) in fields.iter().enumerate() {
+            let duplicate = fields[..i].iter().any(|f| f.name == field.name);
+            assert!(
+                !duplicate,
+                "[xbits] duplicate layout field `{}`",
+                field.name
+            );
+        }
+        Layout { fields }
+    }
+
+    /// Returns the fields
+    #[inline(always)]
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Returns the number of bits of all fields
+    pub fn bits(&self) -> usize {
+        self.fields.iter().map(|f| f.width).sum()
+    }
+
+    /// The fields with their bit offsets
+    fn offsets(&self) -> impl Iterator<Item = (usize, &Field)> {
+        self.fields.iter().scan(0, |offset, field| {
+            *offset += field.width;
+            Some((*offset - field.width, field))
+        })
+    }
+
+    /// Read the fields from the first bits, the values not named of enumerated fields are `Value::Uint`
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if there are less bits than the layout
+    pub fn parse<O: BitOrder>(&self, bits: &BitsRef<'_, O>) -> Result<Record> {
+        let values = self
+            .offsets()
+            .map(|(offset, field)| Ok((field.name.clone(), field.read(bits, offset)?)))
+            .collect::<Result<_>>()?;
+        Ok(Record { values })
+    }
+
+    /// Pack the values of the record, the values not in the layout are ignored
+    /// # Errors
+    /// - `Error::Undefined` if a field is not in the record, or an enumerated name is not defined
+    /// - `Error::Overflow` if a value does not fit in its field
+    pub fn encode(&self, record: &Record) -> Result<Bits> {
+        self.encode_in::<Msb0>(record)
+    }
+
+    /// Pack the values of the record in the bit order `O`
+    pub fn encode_in<O: BitOrder>(&self, record: &Record) -> Result<Bits<O>> {
+        let mut bits = Bits::new(self.bits());
+        let data = bits.as_bits_mut().0;
+        for (offset, field) in self.offsets() {
+            let value = record.get(&field.name).ok_or(Error::Undefined)?;
+            field.write::<O>(data, offset, value)?;
+        }
+        Ok(bits)
+    }
+
+    /// Annotate the fields of the bits: the bits by byte, then a line of each field
+    /// with its bit range, its raw bits and its value
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if there are less bits than the layout
+    pub fn dump<O: BitOrder>(&self, bits: &BitsRef<'_, O>) -> Result<String> {
+        let record = self.parse(bits)?;
+        let rows: Vec<(String, &str, String, &Value)> = self
+            .offsets()
+            .zip(record.iter())
+            .map(|((offset, field), (name, value))| {
+                let range = offset..offset + field.width;
+                // the field bits in their order, packed from the highest bit of the first byte
+                let packed = Vec::<u8>::from_bits(bits.range(range.clone()).iter());
+                let mut raw = packed.bit_fmt().replace(' ', "");
+                raw.truncate(field.width);
+                (format!("{range:?}"), name, raw, value)
+            })
+            .collect();
+
+        let width = |column: fn(&(String, &str, String, &Value)) -> usize| {
+            rows.iter().map(column).max().unwrap_or(0)
+        };
+        let (range_width, name_width) = (width(|r| r.0.len()), width(|r| r.1.len()));
+        let raw_width = width(|r| r.2.len());
+
+        let mut dump = format!("{bits}\n");
+        for (range, name, raw, value) in &rows {
+            writeln!(
+                dump,
+                "{range:range_width$}  {name:name_width$}  {raw:raw_width$}  {value}"
+            )
+            .unwrap();
+        }
+        Ok(dump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsBits, Lsb0};
+
+    fn layout() -> Layout {
+        Layout::new([
+            Field::uint("version", 3),
+            Field::enumerated("kind", 2, [(0, "p2pkh"), (1, "p2wpkh"), (2, "p2tr")]),
+            Field::int("delta", 9),
+            Field::uint("timestamp", 48),
+        ])
+    }
+
+    #[test]
+    fn test_parse_encode() {
+        let layout = layout();
+        assert_eq!(layout.bits(), 62);
+        let record = Record::new()
+            .with("timestamp", Value::Uint(0x0123_4567_89ab))
+            .with("version", Value::Uint(7))
+            .with("kind", Value::Enum("p2tr".into()))
+            .with("delta", Value::Int(-256));
+
+        let bits = layout.encode(&record).unwrap();
+        assert_eq!(bits.len(), 62);
+        assert_eq!(bits.as_bytes()[..2], [0b1111_0100, 0b0000_0000]);
+        let parsed = layout.parse(&bits.as_bits()).unwrap();
+        let names: Vec<&str> = parsed.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["version", "kind", "delta", "timestamp"]);
+        assert!(
+            record
+                .iter()
+                .all(|(name, value)| parsed.get(name) == Some(value))
+        );
+
+        let bits = layout.encode_in::<Lsb0>(&record).unwrap();
+        let parsed_lsb0 = layout.parse(&bits.as_bytes().as_bits_in::<Lsb0>()).unwrap();
+        assert_eq!(parsed_lsb0, parsed);
+
+        // not named values
+        let parsed = layout.parse(&[0b0001_1000; 8].as_bits()).unwrap();
+        assert_eq!(parsed.get("kind"), Some(&Value::Uint(3)));
+        assert_eq!(layout.encode(&parsed).unwrap().as_bytes(), [0b0001_1000; 8]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let layout = layout();
+        assert_eq!(layout.parse(&[0; 7].as_bits()), Err(Error::UnexpectedEnd));
+
+        let mut record = Record::new()
+            .with("version", Value::Uint(8))
+            .with("kind", Value::Uint(0))
+            .with("delta", Value::Int(0))
+            .with("timestamp", Value::Uint(0));
+        assert_eq!(layout.encode(&record).err(), Some(Error::Overflow));
+        record.set("version", Value::Int(-1));
+        assert_eq!(layout.encode(&record).err(), Some(Error::Overflow));
+        record.set("version", Value::Int(1));
+        record.set("delta", Value::Int(256));
+        assert_eq!(layout.encode(&record).err(), Some(Error::Overflow));
+        record.set("delta", Value::Uint(255));
+        assert!(layout.encode(&record).is_ok());
+
+        record.set("kind", Value::Enum("p2sh".into()));
+        assert_eq!(layout.encode(&record).err(), Some(Error::Undefined));
+        record.set("kind", Value::Uint(1));
+        record.set("version", Value::Enum("p2tr".into()));
+        assert_eq!(layout.encode(&record).err(), Some(Error::Undefined));
+        assert_eq!(layout.encode(&Record::new()).err(), Some(Error::Undefined));
+    }
+
+    #[test]
+    fn test_dump() {
+        let layout = Layout::new([
+            Field::uint("version", 3),
+            Field::enumerated("network", 1, [(0, "main"), (1, "test")]),
+            Field::int("offset", 12),
+        ]);
+        let dump = layout.dump(&[0b1011_1111, 0b1111_1110].as_bits()).unwrap();
+        assert_eq!(
+            dump,
+            "10111111 11111110\n\
+             0..3   version  101           5\n\
+             3..4   network  1             test\n\
+             4..16  offset   111111111110  -2\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate")]
+    fn test_duplicate() {
+        Layout::new([Field::uint("a", 1), Field::int("a", 2)]);
+    }
+}
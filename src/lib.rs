@@ -48,11 +48,43 @@
 //! );
 //! ```
 
+mod bloom;
+pub mod codes;
 pub mod core;
+mod cursor;
+mod elias_fano;
+mod error;
+mod gcs;
+mod hash;
+pub mod layout;
+mod pack;
+mod packed;
+mod range;
+mod rank;
+mod stream;
+#[cfg(test)]
+mod test_util;
+mod uint;
+pub mod varint;
+mod words;
 mod xbits;
 
-pub use core::{FromBits, ToBits};
+pub use bloom::{BloomFilter, BloomHash};
+pub use core::{BitOrder, Bits, FromBits, Lsb0, Msb0, ToBits};
+pub use cursor::BitCursor;
+pub use elias_fano::EliasFano;
+pub use error::{Error, Result};
+pub use gcs::GcsFilter;
+pub use pack::BitPack;
+pub use packed::PackedInts;
+pub use range::{BitsRange, BitsRangeMut, RangeMut, RangeRef};
+pub use rank::RankSelect;
+pub use stream::{BitReader, BitWriter};
+pub use uint::{U256, U512, UInt};
+pub use words::{AsWordBits, AsWordBitsMut, Be, Endian, Le, Word, WordsMut, WordsRef};
 pub use xbits::{AsBits, AsBitsMut, BitsMut, BitsRef};
+#[cfg(feature = "derive")]
+pub use xbits_derive::BitPack;
 
 /// Assert overflow of parameter
 /// # Parameters
@@ -0,0 +1,51 @@
+use crate::{Bits, BitsRef, Result};
+
+/// A structure packed into bits field by field, the first field is at the first bits.
+/// Each field takes the lowest bits of its value, the highest bit first,
+/// the same layout `from_bit_chunks` makes of the values.
+///
+/// It is derived by `#[derive(BitPack)]` of the `derive` feature,
+/// the fields are `bool` or integers up to 64 bits, `#[bits(n)]` sets the width of a field,
+/// signed fields are sign extended when unpacked.
+/// ```
+/// # use xbits::{AsBits, BitPack, Bits, BitsRef, Result};
+/// struct Header {
+///     version: u8, // 3 bits
+///     flags: u16,  // 13 bits
+/// }
+///
+/// impl BitPack for Header {
+///     const BITS: usize = 16;
+///
+///     fn to_bits(&self) -> Bits {
+///         let mut bits = Bits::new(0);
+///         bits.push_bits(self.version as u64, 3);
+///         bits.push_bits(self.flags as u64, 13);
+///         bits
+///     }
+///
+///     fn from_bits(bits: &BitsRef) -> Result<Self> {
+///         Ok(Header {
+///             version: bits.read_uint(0, 3)? as u8,
+///             flags: bits.read_uint(3, 13)? as u16,
+///         })
+///     }
+/// }
+///
+/// let header = Header { version: 5, flags: 7 };
+/// assert_eq!(header.to_bits().as_bytes(), [0b1010_0000, 0b0000_0111]);
+/// let header = Header::from_bits(&[0b0110_0000, 0b0000_0001].as_bits()).unwrap();
+/// assert_eq!((header.version, header.flags), (3, 1));
+/// ```
+pub trait BitPack: Sized {
+    /// The number of bits of the packed structure
+    const BITS: usize;
+
+    /// Pack the fields into `BITS` bits
+    fn to_bits(&self) -> Bits;
+
+    /// Unpack the fields from the first `BITS` bits, the rest bits are ignored
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if there are less than `BITS` bits
+    fn from_bits(bits: &BitsRef) -> Result<Self>;
+}
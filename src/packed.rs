@@ -0,0 +1,222 @@
+use crate::core::{BitOrder, Msb0, read_field, write_field};
+use crate::{Error, Result, assert_range};
+use std::marker::PhantomData;
+
+/// An array of unsigned integers packed in `width` bits each in the bit order `O`.
+/// The bytes are the same as `from_bit_chunks_in` of the values, tail padding zero,
+/// so a packed buffer can be read and edited in place.
+/// ```
+/// # use xbits::{FromBits, PackedInts};
+/// // 11 bits mnemonic indexes
+/// let data = Vec::from_bit_chunks([2047_u16, 0, 1234].into_iter(), 11);
+/// let mut indexes: PackedInts = PackedInts::from_bytes(data.clone(), 11, 3).unwrap();
+/// assert_eq!(indexes.get(2), Some(1234));
+///
+/// indexes.set(1, 5);
+/// indexes.push(42);
+/// assert_eq!(indexes.iter().collect::<Vec<_>>(), [2047, 5, 1234, 42]);
+/// assert_eq!(
+///     indexes.as_bytes(),
+///     Vec::from_bit_chunks([2047_u16, 5, 1234, 42].into_iter(), 11)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedInts<O: BitOrder = Msb0> {
+    data: Vec<u8>,
+    width: usize, // bits of each value
+    len: usize,   // number of values
+    order: PhantomData<O>,
+}
+
+impl<O: BitOrder> PackedInts<O> {
+    /// Create an empty array of `width` bits values
+    /// # Parameters
+    /// - `width`: the bits of each value, 1 <= width <= 64
+    pub fn new(width: usize) -> Self {
+        assert_range!(width, 1, 64, "PackedInts");
+        PackedInts {
+            data: vec![],
+            width,
+            len: 0,
+            order: PhantomData,
+        }
+    }
+
+    /// Create an array of `len` zeros
+    /// # Parameters
+    /// - `width`: the bits of each value, 1 <= width <= 64
+    pub fn zeros(width: usize, len: usize) -> Self {
+        let mut values = Self::new(width);
+        values.data = vec![0; (len * width).div_ceil(8)];
+        values.len = len;
+        values
+    }
+
+    /// Pack the lowest `width` bits of each value
+    /// # Parameters
+    /// - `width`: the bits of each value, 1 <= width <= 64
+    pub fn from_iter<I: IntoIterator<Item = u64>>(width: usize, values: I) -> Self {
+        let mut packed = Self::new(width);
+        values.into_iter().for_each(|value| packed.push(value));
+        packed
+    }
+
+    /// Take a packed buffer of `len` values
+    /// # Parameters
+    /// - `width`: the bits of each value, 1 <= width <= 64
+    /// # Errors
+    /// - `Error::LengthMismatch` if the buffer is not `len * width` bits in whole bytes
+    pub fn from_bytes(data: Vec<u8>, width: usize, len: usize) -> Result<Self> {
+        let mut values = Self::new(width);
+        if len.checked_mul(width).map(|bits| bits.div_ceil(8)) != Some(data.len()) {
+            return Err(Error::LengthMismatch);
+        }
+        (values.data, values.len) = (data, len);
+        Ok(values)
+    }
+
+    /// Returns the bits of each value
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of values
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no values
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the packed bytes
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the packed bytes
+    #[inline(always)]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the `i`-th value
+    /// # Returns
+    /// - `None` if `i >= len`
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> Option<u64> {
+        match i < self.len {
+            true => Some(read_field::<O>(&self.data, i * self.width, self.width)),
+            false => None,
+        }
+    }
+
+    /// Set the `i`-th value to the lowest `width` bits of the value
+    /// # Panics
+    /// - if `i >= len`
+    #[inline(always)]
+    pub fn set(&mut self, i: usize, value: u64) {
+        assert!(i < self.len, "[xbits] PackedInts index out of range");
+        write_field::<O>(&mut self.data, i * self.width, self.width, value);
+    }
+
+    /// Append the lowest `width` bits of the value
+    pub fn push(&mut self, value: u64) {
+        self.len += 1;
+        self.data.resize((self.len * self.width).div_ceil(8), 0);
+        self.set(self.len - 1, value);
+    }
+
+    /// Remove the last value
+    pub fn pop(&mut self) -> Option<u64> {
+        let value = self.get(self.len.checked_sub(1)?)?;
+        self.set(self.len - 1, 0);
+        self.len -= 1;
+        self.data.truncate((self.len * self.width).div_ceil(8));
+        Some(value)
+    }
+
+    /// Iterator the values
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u64> + ExactSizeIterator + '_ {
+        (0..self.len).map(|i| read_field::<O>(&self.data, i * self.width, self.width))
+    }
+}
+
+impl<O: BitOrder> Extend<u64> for PackedInts<O> {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, values: I) {
+        values.into_iter().for_each(|value| self.push(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lsb0;
+    use crate::core::{BitIterator, FromBits};
+    use crate::test_util;
+
+    fn check<O: BitOrder>(width: usize) {
+        let mask = u64::MAX >> (64 - width);
+        let values = test_util::values(100, width);
+        let mut packed = PackedInts::<O>::from_iter(width, values.iter().copied());
+        assert_eq!(packed.len(), values.len());
+        assert_eq!(packed.iter().collect::<Vec<_>>(), values);
+        assert_eq!(packed.as_bytes().len(), (values.len() * width).div_ceil(8));
+        if width <= 32 {
+            let chunks = Vec::from_bit_chunks_in::<O, _, _>(values.iter().copied(), width);
+            assert_eq!(packed.as_bytes(), chunks);
+            let read: Vec<u64> = chunks
+                .bit_chunks_in::<O, u64>(width)
+                .take(values.len())
+                .collect();
+            assert_eq!(read, values);
+        }
+
+        // set in place keeps the neighbours
+        let mut expected = values.clone();
+        for i in (0..values.len()).step_by(7) {
+            packed.set(i, !values[i]);
+            expected[i] = !values[i] & mask;
+        }
+        assert_eq!(packed.iter().collect::<Vec<_>>(), expected);
+
+        while let Some(value) = packed.pop() {
+            assert_eq!(Some(value), expected.pop());
+            let bytes = (packed.len() * width).div_ceil(8);
+            assert_eq!(packed.as_bytes().len(), bytes);
+        }
+        assert!(packed.as_bytes().is_empty());
+    }
+
+    #[test]
+    fn test_packed_ints() {
+        for width in [1, 3, 8, 11, 13, 31, 32, 33, 57, 63, 64] {
+            check::<Msb0>(width);
+            check::<Lsb0>(width);
+        }
+
+        let data = Vec::from_bit_chunks_in::<Lsb0, _, _>([0b10_0011_u8, 0b00_0110].into_iter(), 6);
+        let mut packed = PackedInts::<Lsb0>::from_bytes(data, 6, 2).unwrap();
+        assert_eq!(packed.get(1), Some(0b00_0110));
+        assert_eq!(packed.get(2), None);
+        packed.extend([0b11_1111]);
+        assert_eq!(packed.as_bytes(), [0b1010_0011, 0b1111_0001, 0b0000_0011]);
+
+        assert_eq!(
+            PackedInts::<Msb0>::from_bytes(vec![0; 3], 11, 3),
+            Err(Error::LengthMismatch)
+        );
+        assert_eq!(PackedInts::<Msb0>::zeros(11, 3).as_bytes(), [0; 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_invalid_width() {
+        PackedInts::<Msb0>::new(65);
+    }
+}
@@ -1,19 +1,234 @@
+use crate::core::{BitOrder, Bitwise, Msb0, RangeExtend};
+use std::marker::PhantomData;
 use std::ops::Range;
 
+/// Bit range view of a byte array
 pub trait BitsRange {
-    fn bits_range(&self, range: Range<usize>) -> RangeRef;
+    /// Get a view to the bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::BitsRange;
+    /// assert_eq!([0b0001_1000, 0b1000_0011].bits_range(4..9).count_ones(), 2);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits
+    fn bits_range(&self, range: Range<usize>) -> RangeRef<'_>;
 }
 
+/// Mutable bit range view of a byte array
 pub trait BitsRangeMut {
-    fn bits_range_mut(&mut self, range: Range<usize>) -> RangeMut;
+    /// Get a mutable view to the bits in the range
+    /// # Examples
+    /// ```
+    /// # use xbits::BitsRangeMut;
+    /// let mut data = [0_u8, 0];
+    /// data.bits_range_mut(4..9).fill(true);
+    /// assert_eq!(data, [0b0000_1111, 0b1000_0000]);
+    /// ```
+    /// # Panics
+    /// - if the range is out of the bits
+    fn bits_range_mut(&mut self, range: Range<usize>) -> RangeMut<'_>;
 }
 
-pub struct RangeRef<'a> {
+impl BitsRange for [u8] {
+    #[inline(always)]
+    fn bits_range(&self, range: Range<usize>) -> RangeRef<'_> {
+        RangeRef::new(self, range)
+    }
+}
+
+impl BitsRangeMut for [u8] {
+    #[inline(always)]
+    fn bits_range_mut(&mut self, range: Range<usize>) -> RangeMut<'_> {
+        RangeMut::new(self, range)
+    }
+}
+
+/// A view to a bit range of a byte array, the partial bytes at both ends are allowed.
+/// ```
+/// # use xbits::AsBits;
+/// let data = [0b0001_1000, 0b1000_0011];
+/// let range = data.as_bits().range(4..9);
+/// assert_eq!((range.len(), range.count_ones(), range.count_zeros()), (5, 2, 3));
+/// assert_eq!(range.iter().collect::<Vec<_>>(), [true, false, false, false, true]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeRef<'a, O: BitOrder = Msb0> {
     data: &'a [u8],
     range: Range<usize>,
+    order: PhantomData<O>,
 }
 
-pub struct RangeMut<'a> {
+/// A mutable view to a bit range of a byte array.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RangeMut<'a, O: BitOrder = Msb0> {
     data: &'a mut [u8],
     range: Range<usize>,
+    order: PhantomData<O>,
+}
+
+impl<'a, O: BitOrder> RangeRef<'a, O> {
+    /// # Panics
+    /// - if the range is out of the bits
+    pub(crate) fn new(data: &'a [u8], range: Range<usize>) -> Self {
+        let end = range.end;
+        crate::assert_range!(end, range.start, data.len() * 8, "range");
+        RangeRef {
+            data,
+            range,
+            order: PhantomData,
+        }
+    }
+
+    /// Returns true if the range is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Returns the number of bits in the range.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Get the bit at `index` relative to the range start
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len(), "[xbits] range index out of bounds");
+        self.data.bit_get_in::<O>(self.range.start + index)
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.range.clone().map(|i| self.data.bit_get_in::<O>(i))
+    }
+
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        self.data.range_count_ones::<O>(self.range.clone())
+    }
+
+    #[inline(always)]
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns true if the number of one bits is odd
+    #[inline(always)]
+    pub fn parity(&self) -> bool {
+        self.data.range_parity::<O>(self.range.clone())
+    }
+
+    /// Count the number of different bits of two ranges in the same length
+    /// # Panics
+    /// - if the lengths of the ranges are different
+    /// # Examples
+    /// ```
+    /// # use xbits::AsBits;
+    /// let (received, expected) = ([0b0110_1001_u8, 0b1100_0000], [0b1110_1001_u8, 0b0110_0000]);
+    /// assert_eq!(received.as_bits().range(1..10).hamming_distance(&expected.as_bits().range(1..10)), 1);
+    /// assert_eq!(received.as_bits().range(4..8).hamming_distance(&expected.as_bits().range(12..16)), 2);
+    /// ```
+    #[inline(always)]
+    pub fn hamming_distance(&self, other: &RangeRef<'_, O>) -> usize {
+        self.data
+            .range_hamming_distance::<O>(self.range.clone(), other.data, other.range.clone())
+    }
+}
+
+impl<'a, O: BitOrder> RangeMut<'a, O> {
+    /// # Panics
+    /// - if the range is out of the bits
+    pub(crate) fn new(data: &'a mut [u8], range: Range<usize>) -> Self {
+        let end = range.end;
+        crate::assert_range!(end, range.start, data.len() * 8, "range");
+        RangeMut {
+            data,
+            range,
+            order: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn to_ref(&self) -> RangeRef<'_, O> {
+        RangeRef::new(self.data, self.range.clone())
+    }
+
+    /// Set the bit at `index` relative to the range start
+    #[inline(always)]
+    pub fn set(self, index: usize, value: bool) -> Self {
+        assert!(
+            index < self.range.len(),
+            "[xbits] range index out of bounds"
+        );
+        self.data.bit_set_in::<O>(self.range.start + index, value);
+        self
+    }
+
+    #[inline(always)]
+    pub fn fill(self, value: bool) -> Self {
+        self.data.range_fill::<O>(self.range.clone(), value);
+        self
+    }
+
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        self.data.range_not::<O>(self.range.clone());
+        self
+    }
+}
+
+impl<O: BitOrder> std::fmt::Display for RangeRef<'_, O> {
+    /// Format the bits in the range
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bits: String = self.iter().map(|b| if b { '1' } else { '0' }).collect();
+        write!(f, "{bits}")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use crate::{AsBits, AsBitsMut, BitsRange, BitsRangeMut, Lsb0};
+
+    #[test]
+    fn test_range_view() {
+        let mut data = [0b1010_0000_u8, 0b0000_0101, 0xff];
+        let range = data.as_bits().range(2..14);
+        assert_eq!(range.to_string(), "100000000001");
+        assert_eq!((range.count_ones(), range.parity()), (2, false));
+        assert_eq!(range.get(11), true);
+
+        let range = data.as_bits_in::<Lsb0>().range(2..14);
+        assert_eq!(range.to_string(), "000101101000");
+        assert_eq!(range.count_ones(), 4);
+        assert_eq!(
+            range.hamming_distance(&data.as_bits_in::<Lsb0>().range(10..22)),
+            7
+        );
+
+        data.as_bits_mut().range(4..20).not().set(0, false);
+        assert_eq!(data, [0b1010_0111, 0b1111_1010, 0b0000_1111]);
+        data.as_bits_mut_in::<Lsb0>().range(4..20).fill(false);
+        assert_eq!(data, [0b0000_0111, 0b0000_0000, 0b0000_0000]);
+        assert_eq!(data.as_bits().range(0..24).count_zeros(), 21);
+        assert_eq!(data.as_bits().range(24..24).is_empty(), true);
+    }
+
+    #[test]
+    fn test_bits_range() {
+        let mut data = vec![0b1010_0000_u8, 0b0000_0101, 0xff];
+        assert_eq!(data.bits_range(2..14), data.as_bits().range(2..14));
+        assert_eq!(data.bits_range(2..14).to_string(), "100000000001");
+        data.bits_range_mut(4..20).not();
+        assert_eq!(data, [0b1010_1111, 0b1111_1010, 0b0000_1111]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_out_of_bounds() {
+        [0_u8; 2].as_bits().range(3..17);
+    }
 }
@@ -0,0 +1,280 @@
+use crate::BitsRef;
+use crate::core::{BitOrder, Msb0, indexed_word};
+use std::marker::PhantomData;
+
+/// Bits of a word read at a time
+const WORD_BITS: usize = u64::BITS as usize;
+/// Words in a block, each block has a 16 bits relative count
+const BLOCK_WORDS: usize = 4;
+/// Blocks in a superblock, each superblock has a 64 bits absolute count
+const SUPER_BLOCKS: usize = 8;
+
+/// A succinct rank/select index over a bit buffer.
+/// The ones are counted before every superblock of 2048 bits and every block of 256 bits,
+/// the index takes about 9.4% space of the bits.
+/// - `rank1`, `rank0` run in O(1) time
+/// - `select1`, `select0` run in O(log n) time
+/// ```
+/// # use xbits::{AsBits, RankSelect};
+/// let data = [0b1000_0000, 0b0100_0001];
+/// let index = RankSelect::new(data.as_bits());
+/// assert_eq!(index.rank1(9), 1);
+/// assert_eq!(index.rank0(9), 8);
+/// assert_eq!(index.select1(2), Some(15));
+/// assert_eq!(index.select0(0), Some(1));
+/// assert_eq!(index.select1(3), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RankSelect<'a, O: BitOrder = Msb0> {
+    data: &'a [u8],
+    index: RankIndex<O>,
+}
+
+impl<'a, O: BitOrder> RankSelect<'a, O> {
+    /// Build the index of the bits
+    pub fn new(bits: BitsRef<'a, O>) -> Self {
+        RankSelect {
+            data: bits.0,
+            index: RankIndex::new(bits.0),
+        }
+    }
+
+    /// Returns the number of bits
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len() * 8
+    }
+
+    /// Returns true if there are no bits
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of one bits
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        self.index.count_ones()
+    }
+
+    /// Returns the number of zero bits
+    #[inline(always)]
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.index.count_ones()
+    }
+
+    /// Count the one bits before the position `i`
+    /// # Panics
+    /// - if `i > len`
+    #[inline(always)]
+    pub fn rank1(&self, i: usize) -> usize {
+        self.index.rank1(self.data, i)
+    }
+
+    /// Count the zero bits before the position `i`
+    /// # Panics
+    /// - if `i > len`
+    #[inline(always)]
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Position of the `k`-th one bit, counting from 0
+    /// # Returns
+    /// - `None` if there are no more than `k` one bits
+    #[inline(always)]
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        match k < self.count_ones() {
+            true => Some(self.index.select(self.data, k, true)),
+            false => None,
+        }
+    }
+
+    /// Position of the `k`-th zero bit, counting from 0
+    /// # Returns
+    /// - `None` if there are no more than `k` zero bits
+    #[inline(always)]
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        match k < self.count_zeros() {
+            true => Some(self.index.select(self.data, k, false)),
+            false => None,
+        }
+    }
+}
+
+/// The counts of `RankSelect` apart from the bits, for the structures owning their bits.
+/// The data must be the same one the index is built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RankIndex<O: BitOrder> {
+    supers: Vec<u64>, // ones before each superblock
+    blocks: Vec<u16>, // ones before each block, relative to its superblock
+    ones: usize,
+    order: PhantomData<O>,
+}
+
+impl<O: BitOrder> RankIndex<O> {
+    /// Build the index of the data
+    pub(crate) fn new(data: &[u8]) -> Self {
+        let words = (data.len() * 8).div_ceil(WORD_BITS);
+        let mut supers = Vec::with_capacity(words.div_ceil(BLOCK_WORDS * SUPER_BLOCKS));
+        let mut blocks = Vec::with_capacity(words.div_ceil(BLOCK_WORDS));
+
+        let (mut ones, mut relative) = (0, 0);
+        for i in 0..words {
+            if i % (BLOCK_WORDS * SUPER_BLOCKS) == 0 {
+                supers.push(ones as u64);
+                relative = 0;
+            }
+            if i % BLOCK_WORDS == 0 {
+                blocks.push(relative as u16);
+            }
+            let n = indexed_word::<O>(data, i).count_ones() as usize;
+            ones += n;
+            relative += n;
+        }
+        RankIndex {
+            supers,
+            blocks,
+            ones,
+            order: PhantomData,
+        }
+    }
+
+    /// Returns the number of one bits
+    #[inline(always)]
+    pub(crate) fn count_ones(&self) -> usize {
+        self.ones
+    }
+
+    /// Count the one bits before the position `i`
+    pub(crate) fn rank1(&self, data: &[u8], i: usize) -> usize {
+        assert!(i <= data.len() * 8, "[xbits] rank position out of bounds");
+        let (w, k) = (i / WORD_BITS, i % WORD_BITS);
+        let block = w / BLOCK_WORDS;
+        let mut rank = match self.blocks.get(block) {
+            Some(&relative) => self.supers[block / SUPER_BLOCKS] as usize + relative as usize,
+            None => return self.ones, // i == len at the end of a block
+        };
+        for j in block * BLOCK_WORDS..w {
+            rank += indexed_word::<O>(data, j).count_ones() as usize;
+        }
+        if k > 0 {
+            let mask = u64::MAX >> (WORD_BITS - k);
+            rank += (indexed_word::<O>(data, w) & mask).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Position of the `k`-th `bit`, `k` is less than the count of `bit`
+    pub(crate) fn select(&self, data: &[u8], k: usize, bit: bool) -> usize {
+        const SUPER_BITS: usize = WORD_BITS * BLOCK_WORDS * SUPER_BLOCKS;
+        const BLOCK_BITS: usize = WORD_BITS * BLOCK_WORDS;
+        let count = |ones: usize, bits: usize| if bit { ones } else { bits - ones };
+
+        // binary search the last superblock with no more than `k` bits before it
+        let before = |s: usize| count(self.supers[s] as usize, s * SUPER_BITS);
+        let (mut s, mut end) = (0, self.supers.len());
+        while end - s > 1 {
+            let mid = (s + end) / 2;
+            match before(mid) <= k {
+                true => s = mid,
+                false => end = mid,
+            }
+        }
+        let mut k = k - before(s);
+
+        // scan the blocks of the superblock, then the words of the block
+        let first = s * SUPER_BLOCKS;
+        let last = (first + SUPER_BLOCKS).min(self.blocks.len());
+        let b = (first + 1..last)
+            .take_while(|&b| count(self.blocks[b] as usize, (b - first) * BLOCK_BITS) <= k)
+            .last()
+            .unwrap_or(first);
+        k -= count(self.blocks[b] as usize, (b - first) * BLOCK_BITS);
+
+        let mut w = b * BLOCK_WORDS;
+        loop {
+            let mut value = indexed_word::<O>(data, w);
+            if !bit {
+                value = !value;
+            }
+            let n = value.count_ones() as usize;
+            if k < n {
+                // clear the lower one bits of the word
+                for _ in 0..k {
+                    value &= value - 1;
+                }
+                return w * WORD_BITS + value.trailing_zeros() as usize;
+            }
+            k -= n;
+            w += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BitIterator, Lsb0};
+    use crate::test_util::bytes;
+    use crate::{AsBits, Bits};
+
+    fn check<O: BitOrder>(data: &[u8]) {
+        let index = RankSelect::new(data.as_bits_in::<O>());
+        let bits: Bits<O> = data.as_bits_in::<O>().iter().collect();
+        let (mut ones, mut zeros) = (vec![], vec![]);
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(index.rank1(i), ones.len());
+            assert_eq!(index.rank0(i), zeros.len());
+            if bit { ones.push(i) } else { zeros.push(i) }
+        }
+        assert_eq!(index.rank1(data.len() * 8), ones.len());
+        assert_eq!(
+            (index.count_ones(), index.count_zeros()),
+            (ones.len(), zeros.len())
+        );
+        for (k, &i) in ones.iter().enumerate() {
+            assert_eq!(index.select1(k), Some(i));
+        }
+        for (k, &i) in zeros.iter().enumerate() {
+            assert_eq!(index.select0(k), Some(i));
+        }
+        assert_eq!(index.select1(ones.len()), None);
+        assert_eq!(index.select0(zeros.len()), None);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        for len in [0, 1, 7, 8, 31, 32, 33, 255, 256, 257, 700] {
+            let data = bytes(len, 0);
+            check::<Msb0>(&data);
+            check::<Lsb0>(&data);
+
+            // sparse bits
+            let sparse: Vec<u8> = (0..len)
+                .map(|i| if i % 37 == 5 { 0x10 } else { 0 })
+                .collect();
+            check::<Msb0>(&sparse);
+            let dense: Vec<u8> = sparse.iter().map(|b| !b).collect();
+            check::<Lsb0>(&dense);
+        }
+    }
+
+    #[test]
+    fn test_space_overhead() {
+        let data = vec![0x5a; 1 << 16];
+        let index = RankSelect::new(data.as_bits());
+        let overhead = index.index.supers.len() * 8 + index.index.blocks.len() * 2;
+        assert!(overhead * 100 / data.len() < 10);
+        assert_eq!(
+            index.select1(index.count_ones() - 1),
+            data.as_bits()
+                .iter()
+                .enumerate()
+                .filter(|&(_, b)| b)
+                .map(|(i, _)| i)
+                .last()
+        );
+        assert_eq!(data.bit_iter().filter(|&b| b).count(), index.count_ones());
+    }
+}
@@ -0,0 +1,5 @@
+mod reader;
+mod writer;
+
+pub use reader::BitReader;
+pub use writer::BitWriter;
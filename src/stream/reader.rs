@@ -0,0 +1,302 @@
+use crate::assert_range;
+use crate::core::{BitOrder, Msb0};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::marker::PhantomData;
+
+/// A bit-level reader over `std::io::Read`.
+/// It reads values of 1 to 64 bits from a byte stream without loading it into memory.
+/// ```
+/// # use xbits::BitReader;
+/// let mut reader = BitReader::new(&[0b1111_0000, 0b1010_1010][..]);
+/// assert_eq!(reader.read_bit().unwrap(), true);
+/// assert_eq!(reader.read_bits::<u8>(6).unwrap(), 0b11_1000);
+/// assert_eq!(reader.read_bits::<u16>(9).unwrap(), 0b0_1010_1010);
+/// assert!(reader.read_bit().is_err());
+/// ```
+pub struct BitReader<R, O: BitOrder = Msb0> {
+    inner: R,
+    order: PhantomData<O>,
+    cache: u128,      // pending bits read from the inner stream
+    cache_len: usize, // number of pending bits
+    position: u64,    // number of bits consumed
+}
+
+impl<R: Read> BitReader<R> {
+    /// Create a reader with the most significant bit first order
+    pub fn new(inner: R) -> Self {
+        Self::with_order(inner, Msb0)
+    }
+}
+
+impl<R: Read, O: BitOrder> BitReader<R, O> {
+    /// Create a reader with the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::{BitReader, Lsb0};
+    /// let mut reader = BitReader::with_order(&[0b1111_0000][..], Lsb0);
+    /// assert_eq!(reader.read_bits::<u8>(6).unwrap(), 0b11_0000);
+    /// ```
+    pub fn with_order(inner: R, _order: O) -> Self {
+        BitReader {
+            inner,
+            order: PhantomData,
+            cache: 0,
+            cache_len: 0,
+            position: 0,
+        }
+    }
+
+    /// Read a single bit
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        self.read_bits::<u8>(1).map(|v| v != 0)
+    }
+
+    /// Read `n` bits as an unsigned value
+    /// # Parameters
+    /// - `T`: the type to contain the bits
+    /// - `n`: the number of bits to read
+    /// - 1 <= n <= T::BITS <= 64
+    /// # Errors
+    /// - `UnexpectedEof` if less than `n` bits remain, no bits are consumed
+    /// - `InvalidData` if the value does not fit in `T`, e.g. a signed `T` with the highest bit set,
+    ///   the bits are consumed. Signed values are read by `read_signed`
+    /// # Examples
+    /// ```
+    /// # use xbits::BitReader;
+    /// let mut reader = BitReader::new(&[0xff, 0x7f][..]);
+    /// assert!(reader.read_bits::<i8>(8).is_err());
+    /// assert_eq!(reader.read_bits::<i8>(8).unwrap(), 0x7f);
+    /// ```
+    pub fn read_bits<T>(&mut self, n: usize) -> io::Result<T>
+    where
+        T: TryFrom<u64>,
+    {
+        let valid_size = (std::mem::size_of::<T>() * 8).min(64);
+        assert_range!(n, 1, valid_size, "read_bits");
+
+        if self.fill(n)? < n {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        convert(self.take(n))
+    }
+
+    /// Read `n` bits as a two's-complement signed value
+    /// # Parameters
+    /// - `n`: the number of bits to read, 1 <= n <= 64
+    /// # Examples
+    /// ```
+    /// # use xbits::BitReader;
+    /// let mut reader = BitReader::new(&[0b1110_0110][..]);
+    /// assert_eq!(reader.read_signed(3).unwrap(), -1);
+    /// assert_eq!(reader.read_signed(5).unwrap(), 6);
+    /// ```
+    pub fn read_signed(&mut self, n: usize) -> io::Result<i64> {
+        let value = self.read_bits::<u64>(n)?;
+        let shift = u64::BITS as usize - n;
+        Ok(((value << shift) as i64) >> shift)
+    }
+
+    /// Skip `n` bits
+    /// # Errors
+    /// - `UnexpectedEof` if less than `n` bits remain, all the remaining bits are consumed
+    pub fn skip_bits(&mut self, n: u64) -> io::Result<()> {
+        let cached = n.min(self.cache_len as u64) as usize;
+        (0..cached).step_by(64).for_each(|i| {
+            self.take((cached - i).min(64));
+        });
+
+        // skip whole bytes without caching them
+        let n = n - cached as u64;
+        let bytes = n / 8;
+        let skipped = io::copy(&mut (&mut self.inner).take(bytes), &mut io::sink())?;
+        self.position += skipped * 8;
+        if skipped < bytes {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let m = (n % 8) as usize;
+        if self.fill(m)? < m {
+            self.take(self.cache_len);
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.take(m);
+        Ok(())
+    }
+
+    /// Skip the remaining bits of the current byte
+    pub fn align_to_byte(&mut self) {
+        self.take(self.cache_len % 8);
+    }
+
+    /// Returns true if the position is at a byte boundary
+    pub fn is_aligned(&self) -> bool {
+        self.position.is_multiple_of(8)
+    }
+
+    /// Returns the number of bits consumed
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Unwraps the inner reader, the cached bytes are lost
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the bits in the stream grouped by n, the same as `bit_chunks`
+    ///
+    /// # Parameters
+    /// - `T`: the type to contain the grouped bits
+    /// - `n`: the number of bits to group
+    /// - 1 <= n <= T::BITS <= 64
+    ///
+    /// # Errors
+    /// - `InvalidData` if a value does not fit in `T`, as `read_bits`
+    ///
+    /// # Examples
+    /// ```
+    /// # use xbits::BitReader;
+    /// let mut reader = BitReader::new(&[0b1111_1111; 3][..]);
+    /// assert_eq!(
+    ///     reader.chunks(11).collect::<Result<Vec<u16>, _>>().unwrap(),
+    ///     vec![0b111_1111_1111, 0b111_1111_1111, 0b110_0000_0000]
+    /// );
+    /// ```
+    pub fn chunks<T>(&mut self, n: usize) -> impl Iterator<Item = io::Result<T>> + '_
+    where
+        T: TryFrom<u64>,
+    {
+        let valid_size = (std::mem::size_of::<T>() * 8).min(64);
+        assert_range!(n, 1, valid_size, "chunks");
+
+        std::iter::from_fn(move || match self.fill(n) {
+            Ok(0) => None,
+            Ok(len) if len < n => {
+                // tail padding zero bits
+                let value = O::push(self.take(len) as u128, len, 0, n - len);
+                Some(convert(value as u64))
+            }
+            Ok(_) => Some(convert(self.take(n))),
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Cache at least `n` bits if the stream is long enough, returns the cached length
+    fn fill(&mut self, n: usize) -> io::Result<usize> {
+        let mut buf = [0; 16];
+        while self.cache_len < n {
+            let size = (u128::BITS as usize - self.cache_len) / 8;
+            let len = match self.inner.read(&mut buf[..size]) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for &b in &buf[..len] {
+                self.cache = O::push(self.cache, self.cache_len, b as u64, 8);
+                self.cache_len += 8;
+            }
+        }
+        Ok(self.cache_len)
+    }
+
+    /// Consume `n` cached bits, 0 <= n <= 64
+    fn take(&mut self, n: usize) -> u64 {
+        debug_assert!(n <= self.cache_len && n <= 64);
+        let (value, rest) = O::pop(self.cache, self.cache_len, n);
+        self.cache = rest;
+        self.cache_len -= n;
+        self.position += n as u64;
+        value
+    }
+}
+
+impl<R: Read + Seek, O: BitOrder> BitReader<R, O> {
+    /// Returns the number of bits remaining in a stream of known size
+    pub fn bits_remaining(&mut self) -> io::Result<u64> {
+        let current = self.inner.stream_position()?;
+        let end = self.inner.seek(SeekFrom::End(0))?;
+        self.inner.seek(SeekFrom::Start(current))?;
+        Ok((end - current) * 8 + self.cache_len as u64)
+    }
+}
+
+/// Convert the bits value to `T`, `InvalidData` if it does not fit
+#[inline(always)]
+fn convert<T: TryFrom<u64>>(value: u64) -> io::Result<T> {
+    value
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "[xbits] bits value overflow"))
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use crate::core::Lsb0;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_bits() {
+        let data = [0x12_u8, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x12];
+        let mut reader = BitReader::new(&data[..]);
+        assert_eq!(reader.read_bits::<u8>(4).unwrap(), 0x1);
+        assert_eq!(reader.read_bits::<u64>(64).unwrap(), 0x2345_6789_abcd_ef01);
+        assert_eq!(reader.read_bit().unwrap(), false);
+        assert_eq!(reader.position(), 69);
+        assert_eq!(
+            reader.read_bits::<u8>(4).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(reader.read_bits::<u8>(3).unwrap(), 0b010);
+
+        let mut reader = BitReader::with_order(&data[..], Lsb0);
+        assert_eq!(reader.read_bits::<u8>(4).unwrap(), 0x2);
+        assert_eq!(reader.read_bits::<u64>(64).unwrap(), 0x2f0d_ebc9_a785_6341);
+        assert_eq!(reader.read_bit().unwrap(), true);
+        assert_eq!(reader.read_signed(3).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_skip_align() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let mut reader = BitReader::new(Cursor::new(&data));
+        assert_eq!(reader.bits_remaining().unwrap(), 2048);
+        reader.skip_bits(3).unwrap();
+        reader.align_to_byte();
+        assert!(reader.is_aligned());
+        assert_eq!(reader.read_bits::<u8>(8).unwrap(), 1);
+        reader.skip_bits(8 * 100 + 4).unwrap();
+        assert_eq!(reader.read_bits::<u8>(4).unwrap(), 102 & 0x0f);
+        assert_eq!(reader.bits_remaining().unwrap(), 2048 - 824);
+        assert!(reader.skip_bits(2048).is_err());
+        assert_eq!(reader.bits_remaining().unwrap(), 0);
+
+        // the tail bits out of a whole byte are consumed at the end
+        let mut reader = BitReader::new(&[0xff, 0xff][..]);
+        reader.skip_bits(5).unwrap();
+        assert!(reader.skip_bits(12).is_err());
+        assert_eq!(reader.position(), 16);
+        let mut reader = BitReader::new(&[0xff][..]);
+        assert!(reader.skip_bits(8 + 3).is_err());
+        assert_eq!(reader.position(), 8);
+        assert!(reader.read_bit().is_err());
+    }
+
+    #[test]
+    fn test_read_overflow() {
+        let mut reader = BitReader::new(&[0xff, 0x7f, 0x80][..]);
+        let err = reader.read_bits::<i8>(8).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(reader.read_bits::<i8>(8).unwrap(), 0x7f);
+        assert_eq!(reader.read_bits::<i16>(8).unwrap(), 0x80);
+
+        let mut reader = BitReader::new(&[0x80, 0x01][..]);
+        let chunks: Vec<io::Result<i8>> = reader.chunks(8).collect();
+        assert_eq!(
+            chunks[0].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(chunks[1].as_ref().unwrap(), &1);
+    }
+}
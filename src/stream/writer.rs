@@ -0,0 +1,220 @@
+use crate::assert_range;
+use crate::core::{BitOrder, Msb0};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+/// A bit-level writer over `std::io::Write`.
+/// It writes values of 1 to 64 bits into a byte stream, the same layout as `from_bit_chunks`.
+///
+/// The partial trailing byte is written, padded with zero bits, by `flush_partial`, `into_inner`
+/// or on drop. Errors on drop are ignored, call `into_inner` to handle them.
+/// ```
+/// # use xbits::BitWriter;
+/// let mut writer = BitWriter::new(vec![]);
+/// writer.write_bit(true).unwrap();
+/// writer.write_bits(0b11_1000, 6).unwrap();
+/// writer.write_bits(0b0_1010_1010, 9).unwrap();
+/// assert_eq!(writer.into_inner().unwrap(), (vec![0b1111_0000, 0b1010_1010], 16));
+/// ```
+pub struct BitWriter<W: Write, O: BitOrder = Msb0> {
+    inner: Option<W>, // taken by `into_inner`
+    order: PhantomData<O>,
+    cache: u128,      // pending bits not written to the inner stream
+    cache_len: usize, // number of pending bits
+    position: u64,    // number of bits written
+}
+
+impl<W: Write> BitWriter<W> {
+    /// Create a writer with the most significant bit first order
+    pub fn new(inner: W) -> Self {
+        Self::with_order(inner, Msb0)
+    }
+}
+
+impl<W: Write, O: BitOrder> BitWriter<W, O> {
+    /// Create a writer with the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::{BitWriter, Lsb0};
+    /// let mut writer = BitWriter::with_order(vec![], Lsb0);
+    /// writer.write_bits(0b11_0000, 6).unwrap();
+    /// assert_eq!(writer.into_inner().unwrap().0, [0b0011_0000]);
+    /// ```
+    pub fn with_order(inner: W, _order: O) -> Self {
+        BitWriter {
+            inner: Some(inner),
+            order: PhantomData,
+            cache: 0,
+            cache_len: 0,
+            position: 0,
+        }
+    }
+
+    /// Write a single bit
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.write_bits(bit as u64, 1)
+    }
+
+    /// Write the lowest `n` bits of the value
+    /// # Parameters
+    /// - `n`: the number of bits to write, 1 <= n <= 64
+    pub fn write_bits(&mut self, value: u64, n: usize) -> io::Result<()> {
+        assert_range!(n, 1, 64, "write_bits");
+        self.cache = O::push(self.cache, self.cache_len, value, n);
+        self.cache_len += n;
+        self.position += n as u64;
+        self.drain()
+    }
+
+    /// Write the lowest `n` bits of a two's-complement signed value
+    /// # Parameters
+    /// - `n`: the number of bits to write, 1 <= n <= 64
+    /// # Examples
+    /// ```
+    /// # use xbits::BitWriter;
+    /// let mut writer = BitWriter::new(vec![]);
+    /// writer.write_signed(-1, 3).unwrap();
+    /// writer.write_signed(6, 5).unwrap();
+    /// assert_eq!(writer.into_inner().unwrap().0, [0b1110_0110]);
+    /// ```
+    pub fn write_signed(&mut self, value: i64, n: usize) -> io::Result<()> {
+        self.write_bits(value as u64, n)
+    }
+
+    /// Pad the current byte with `padding` bits
+    pub fn align(&mut self, padding: bool) -> io::Result<()> {
+        match self.cache_len % 8 {
+            0 => Ok(()),
+            m => self.write_bits(if padding { u64::MAX } else { 0 }, 8 - m),
+        }
+    }
+
+    /// Returns true if the position is at a byte boundary
+    pub fn is_aligned(&self) -> bool {
+        self.position.is_multiple_of(8)
+    }
+
+    /// Returns the number of bits written
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Write the partial byte padded with zero bits, then flush the inner stream.
+    /// The following bits start from a new byte.
+    pub fn flush_partial(&mut self) -> io::Result<()> {
+        self.align(false)?;
+        self.inner_mut().flush()
+    }
+
+    /// Write the partial byte padded with zero bits, unwraps the inner writer
+    /// # Returns
+    /// - the inner writer and the number of bits written, excluding the final padding
+    pub fn into_inner(mut self) -> io::Result<(W, u64)> {
+        let position = self.position;
+        self.flush_partial()?;
+        Ok((self.inner.take().unwrap(), position))
+    }
+
+    #[inline(always)]
+    fn inner_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap() // only taken when the writer is consumed
+    }
+
+    /// Write the whole cached bytes to the inner stream
+    fn drain(&mut self) -> io::Result<()> {
+        let mut bytes = [0; 16];
+        let n = self.cache_len / 8;
+        for byte in bytes.iter_mut().take(n) {
+            let (value, rest) = O::pop(self.cache, self.cache_len, 8);
+            (*byte, self.cache, self.cache_len) = (value as u8, rest, self.cache_len - 8);
+        }
+        self.inner_mut().write_all(&bytes[..n])
+    }
+}
+
+impl<W: Write, O: BitOrder> Write for BitWriter<W, O> {
+    /// Write whole bytes at the current bit position
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            self.write_bits(b as u64, 8)?;
+        }
+        Ok(buf.len())
+    }
+
+    /// Flush the whole bytes, the partial byte is kept
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()
+    }
+}
+
+impl<W: Write, O: BitOrder> Drop for BitWriter<W, O> {
+    /// Write the partial byte padded with zero bits, the errors are ignored
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.flush_partial();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Lsb0;
+    use crate::{BitReader, FromBits};
+
+    #[test]
+    fn test_write_bits() {
+        let values = [0x1_u64, 0x2345_6789_abcd_ef01, 0x0, 0x2];
+        let widths = [4, 64, 1, 3];
+        fn round_trip<O: BitOrder>(order: O, values: &[u64], widths: &[usize]) {
+            let mut writer = BitWriter::with_order(vec![], order);
+            for (&v, &n) in values.iter().zip(widths) {
+                writer.write_bits(v, n).unwrap();
+            }
+            assert_eq!(writer.position(), 72);
+            let (data, len) = writer.into_inner().unwrap();
+            assert_eq!((data.len(), len), (9, 72));
+
+            let mut reader = BitReader::with_order(&data[..], order);
+            for (&v, &n) in values.iter().zip(widths) {
+                assert_eq!(reader.read_bits::<u64>(n).unwrap(), v);
+            }
+        }
+        round_trip(Msb0, &values, &widths);
+        round_trip(Lsb0, &values, &widths);
+    }
+
+    #[test]
+    fn test_align_flush() {
+        let mut writer = BitWriter::new(vec![]);
+        writer.write_bits(0b101, 3).unwrap();
+        writer.align(true).unwrap();
+        assert!(writer.is_aligned());
+        writer.write_bits(0b1, 1).unwrap();
+        writer.flush_partial().unwrap();
+        writer.write_all(&[0x55]).unwrap();
+        writer.write_signed(-2, 2).unwrap();
+        let (data, len) = writer.into_inner().unwrap();
+        assert_eq!(data, [0b1011_1111, 0b1000_0000, 0x55, 0b1000_0000]);
+        assert_eq!(len, 8 + 8 + 8 + 2);
+
+        // the partial byte is written on drop
+        let mut data = vec![];
+        {
+            let mut writer = BitWriter::new(&mut data);
+            writer.write_bits(0b1010, 12).unwrap();
+        }
+        assert_eq!(data, [0b0000_0000, 0b1010_0000]);
+    }
+
+    #[test]
+    fn test_from_bit_chunks() {
+        let chunks = (0..1000_u16).map(|i| i.wrapping_mul(2017) & 0x7ff);
+        let mut writer = BitWriter::new(vec![]);
+        chunks
+            .clone()
+            .for_each(|v| writer.write_bits(v as u64, 11).unwrap());
+        let (data, _) = writer.into_inner().unwrap();
+        assert_eq!(data, Vec::from_bit_chunks(chunks, 11));
+    }
+}
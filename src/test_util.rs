@@ -0,0 +1,50 @@
+//! Fixtures and bit by bit oracles shared by the unit tests
+use crate::core::{BitOrder, Bitwise};
+
+/// Pseudo-random bytes by the multiplicative hash of the indexes, different for each `seed`
+pub(crate) fn bytes(len: usize, seed: u32) -> Vec<u8> {
+    (0..len as u32)
+        .map(|i| (i.wrapping_add(seed).wrapping_mul(2_654_435_761) >> 24) as u8)
+        .collect()
+}
+
+/// Pseudo-random values in the lowest `width` bits, 1 <= width <= 64
+pub(crate) fn values(len: usize, width: usize) -> Vec<u64> {
+    let mask = u64::MAX >> (64 - width);
+    (0..len as u64)
+        .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) & mask)
+        .collect()
+}
+
+/// Get the bits one by one in the bit order `O`
+pub(crate) fn bits<O: BitOrder>(data: &[u8]) -> Vec<bool> {
+    (0..data.len() * 8)
+        .map(|i| data.bit_get_in::<O>(i))
+        .collect()
+}
+
+/// Read the `width` bits at `offset` one by one, `msb` if the first bit is the highest bit
+pub(crate) fn read_bits<O: BitOrder>(data: &[u8], offset: usize, width: usize, msb: bool) -> u64 {
+    (0..width).fold(0, |value, i| {
+        let bit = data.bit_get_in::<O>(offset + i) as u64;
+        if msb {
+            value << 1 | bit
+        } else {
+            value | bit << i
+        }
+    })
+}
+
+/// Copy the bits one by one, the source bits are read before any is written
+pub(crate) fn copy_bits<O: BitOrder>(
+    data: &mut [u8],
+    dst: usize,
+    src: &[u8],
+    from: usize,
+    len: usize,
+) {
+    let bits: Vec<bool> = (from..from + len).map(|i| src.bit_get_in::<O>(i)).collect();
+    for (i, bit) in bits.into_iter().enumerate() {
+        data.bit_set_in::<O>(dst + i, bit);
+    }
+}
@@ -0,0 +1,487 @@
+/**
+ * `UInt` is a fixed-width unsigned integer stored on the stack as big-endian bytes.
+ * Its operators are implemented by `BitArith` and `Bitwise` on the inner bytes,
+ * so 256-bit hashes and keys can be manipulated like primitive integers.
+ */
+use crate::core::{BitArith, Bitwise};
+use crate::{AsBits, AsBitsMut, BitsMut, BitsRef, Error, Msb0};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Shl, Shr, Sub};
+use std::ops::{AddAssign, BitAndAssign, BitOrAssign, BitXorAssign, MulAssign, SubAssign};
+use std::ops::{DivAssign, RemAssign, ShlAssign, ShrAssign};
+
+/// Fixed-width unsigned integer of `BYTES` big-endian bytes.
+/// Arithmetic operators wrap around on overflow, `checked_*` and `overflowing_*` detect it.
+/// ```
+/// # use xbits::U256;
+/// let a = U256::from(u128::MAX);
+/// assert_eq!(a * a + a + a + U256::ONE, U256::ZERO); // (a + 1)^2 = 2^256 wraps around
+/// assert_eq!((a + U256::ONE).to_string(), "340282366920938463463374607431768211456");
+/// assert_eq!(format!("{:#x}", a >> 64), "0xffffffffffffffff");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UInt<const BYTES: usize>([u8; BYTES]);
+
+/// 256 bits unsigned integer
+pub type U256 = UInt<32>;
+
+/// 512 bits unsigned integer
+pub type U512 = UInt<64>;
+
+impl<const BYTES: usize> UInt<BYTES> {
+    /// The size of this integer type in bits
+    pub const BITS: usize = BYTES * 8;
+
+    /// The smallest value, `0`
+    pub const ZERO: Self = Self([0; BYTES]);
+
+    /// The multiplicative identity, `1`
+    pub const ONE: Self = Self::from_u128(1);
+
+    /// The largest value, all bits are one
+    pub const MAX: Self = Self([0xff; BYTES]);
+
+    /// Create from big-endian bytes
+    #[inline(always)]
+    pub const fn from_be_bytes(bytes: [u8; BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    /// Return the big-endian bytes
+    #[inline(always)]
+    pub const fn to_be_bytes(self) -> [u8; BYTES] {
+        self.0
+    }
+
+    /// Create from a `u128` value
+    /// # Panics
+    /// - if the value does not fit in `BYTES` bytes
+    /// # Examples
+    /// ```
+    /// # use xbits::{UInt, U256};
+    /// const N: U256 = U256::from_u128(0x1234);
+    /// assert_eq!(N.as_bytes()[30..], [0x12, 0x34]);
+    /// assert_eq!(UInt::<2>::from_u128(0xffff), UInt::<2>::MAX);
+    /// ```
+    pub const fn from_u128(value: u128) -> Self {
+        match Self::checked_from_u128(value) {
+            Some(v) => v,
+            None => panic!("[xbits] UInt::from_u128 value overflow"),
+        }
+    }
+
+    /// Create from a `u128` value, `None` if the value does not fit in `BYTES` bytes
+    /// # Examples
+    /// ```
+    /// # use xbits::UInt;
+    /// assert_eq!(UInt::<2>::checked_from_u128(0xffff), Some(UInt::<2>::MAX));
+    /// assert_eq!(UInt::<2>::checked_from_u128(0x1_0000), None);
+    /// ```
+    pub const fn checked_from_u128(value: u128) -> Option<Self> {
+        let bytes = value.to_be_bytes();
+        let mut data = [0; BYTES];
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[bytes.len() - 1 - i];
+            if i < BYTES {
+                data[BYTES - 1 - i] = b;
+            } else if b != 0 {
+                return None;
+            }
+            i += 1;
+        }
+        Some(Self(data))
+    }
+
+    /// Create from a `u64` value
+    /// # Panics
+    /// - if the value does not fit in `BYTES` bytes
+    #[inline(always)]
+    pub const fn from_u64(value: u64) -> Self {
+        Self::from_u128(value as u128)
+    }
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.0.bit_all_zero()
+    }
+
+    #[inline(always)]
+    pub fn leading_zeros(&self) -> usize {
+        self.0.bit_leading_zeros()
+    }
+
+    #[inline(always)]
+    pub fn trailing_zeros(&self) -> usize {
+        self.0.bit_trailing_zeros()
+    }
+
+    /// Calculates `self + rhs`, with a flag whether an overflow occurred
+    pub fn overflowing_add(mut self, rhs: Self) -> (Self, bool) {
+        let overflow = self.0.bit_be_add(&rhs.0);
+        (self, overflow)
+    }
+
+    /// Calculates `self - rhs`, with a flag whether an overflow occurred
+    pub fn overflowing_sub(mut self, rhs: Self) -> (Self, bool) {
+        let overflow = self.0.bit_be_sub(&rhs.0);
+        (self, overflow)
+    }
+
+    /// Calculates `self * rhs`, with a flag whether an overflow occurred
+    pub fn overflowing_mul(mut self, rhs: Self) -> (Self, bool) {
+        let overflow = self.0.bit_be_mul(&rhs.0);
+        (self, overflow)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` if `rhs` is zero
+    pub fn checked_div(mut self, rhs: Self) -> Option<Self> {
+        match self.0.bit_be_div(&rhs.0) {
+            false => Some(self),
+            true => None,
+        }
+    }
+
+    /// Returns `None` if `rhs` is zero
+    pub fn checked_rem(mut self, rhs: Self) -> Option<Self> {
+        match self.0.bit_be_rem(&rhs.0) {
+            false => Some(self),
+            true => None,
+        }
+    }
+
+    /// Calculates the quotient and the remainder of `self / rhs`
+    /// # Panics
+    /// - if `rhs` is zero
+    pub fn div_rem(self, rhs: Self) -> (Self, Self) {
+        match (self.checked_div(rhs), self.checked_rem(rhs)) {
+            (Some(q), Some(r)) => (q, r),
+            _ => panic!("attempt to divide by zero"),
+        }
+    }
+
+    /// Divide by a small divisor in place, returns the remainder
+    fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let mut rem: u128 = 0;
+        for b in self.0.iter_mut() {
+            rem = (rem << 8) | *b as u128;
+            *b = (rem / divisor as u128) as u8;
+            rem %= divisor as u128;
+        }
+        rem as u64
+    }
+}
+
+impl<const BYTES: usize> Default for UInt<BYTES> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+macro_rules! impl_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $method:ident) => {
+        impl<const BYTES: usize> $op for UInt<BYTES> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $fn(mut self, rhs: Self) -> Self {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl<const BYTES: usize> $op_assign for UInt<BYTES> {
+            #[inline(always)]
+            fn $fn_assign(&mut self, rhs: Self) {
+                self.0.$method(&rhs.0);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign, bit_be_add);
+impl_op!(Sub, sub, SubAssign, sub_assign, bit_be_sub);
+impl_op!(Mul, mul, MulAssign, mul_assign, bit_be_mul);
+impl_op!(BitAnd, bitand, BitAndAssign, bitand_assign, bit_be_and);
+impl_op!(BitOr, bitor, BitOrAssign, bitor_assign, bit_be_or);
+impl_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, bit_be_xor);
+
+macro_rules! impl_div_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $method:ident) => {
+        impl<const BYTES: usize> $op for UInt<BYTES> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $fn(mut self, rhs: Self) -> Self {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl<const BYTES: usize> $op_assign for UInt<BYTES> {
+            fn $fn_assign(&mut self, rhs: Self) {
+                if self.0.$method(&rhs.0) {
+                    panic!("attempt to divide by zero");
+                }
+            }
+        }
+    };
+}
+
+impl_div_op!(Div, div, DivAssign, div_assign, bit_be_div);
+impl_div_op!(Rem, rem, RemAssign, rem_assign, bit_be_rem);
+
+macro_rules! impl_shift_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $method:ident) => {
+        impl<const BYTES: usize> $op<usize> for UInt<BYTES> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $fn(mut self, rhs: usize) -> Self {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl<const BYTES: usize> $op_assign<usize> for UInt<BYTES> {
+            #[inline(always)]
+            fn $fn_assign(&mut self, rhs: usize) {
+                self.0.$method(rhs);
+            }
+        }
+    };
+}
+
+impl_shift_op!(Shl, shl, ShlAssign, shl_assign, bit_shl);
+impl_shift_op!(Shr, shr, ShrAssign, shr_assign, bit_shr);
+
+impl<const BYTES: usize> Not for UInt<BYTES> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(mut self) -> Self {
+        self.0.bit_not();
+        self
+    }
+}
+
+/// `From` the unsigned primitives which always fit in `UInt<BYTES>`,
+/// the other values are converted by `checked_from_u128` or `TryFrom<&[u8]>`
+macro_rules! impl_from {
+    ($bytes:literal: $($t:ty),*) => {
+        $(
+            impl From<$t> for UInt<$bytes> {
+                #[inline(always)]
+                fn from(value: $t) -> Self {
+                    Self::from_u128(value as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(1: u8);
+impl_from!(2: u8, u16);
+impl_from!(4: u8, u16, u32);
+impl_from!(8: u8, u16, u32, u64, usize);
+impl_from!(16: u8, u16, u32, u64, u128, usize);
+impl_from!(32: u8, u16, u32, u64, u128, usize);
+impl_from!(64: u8, u16, u32, u64, u128, usize);
+
+impl<const BYTES: usize> TryFrom<&[u8]> for UInt<BYTES> {
+    type Error = Error;
+
+    /// Create from big-endian bytes of any length
+    /// # Errors
+    /// - `Error::Overflow` if the value does not fit in `BYTES` bytes
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let n = value.len().saturating_sub(BYTES);
+        if !value[..n].bit_all_zero() {
+            return Err(Error::Overflow);
+        }
+        let mut data = [0; BYTES];
+        data[BYTES - (value.len() - n)..].copy_from_slice(&value[n..]);
+        Ok(Self(data))
+    }
+}
+
+impl<const BYTES: usize> AsBits for UInt<BYTES> {
+    type Order = Msb0;
+
+    #[inline(always)]
+    fn as_bits(&self) -> BitsRef<'_> {
+        BitsRef::new(&self.0)
+    }
+}
+
+impl<const BYTES: usize> AsBitsMut for UInt<BYTES> {
+    #[inline(always)]
+    fn as_bits_mut(&mut self) -> BitsMut<'_> {
+        BitsMut::new(&mut self.0)
+    }
+}
+
+impl<const BYTES: usize> std::fmt::Display for UInt<BYTES> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // split into groups of 19 decimal digits, the most fit in a u64
+        const GROUP: u64 = 10_000_000_000_000_000_000;
+        let mut value = *self;
+        let mut groups = vec![];
+        loop {
+            let rem = value.div_rem_u64(GROUP);
+            if value.is_zero() {
+                groups.push(rem.to_string());
+                break;
+            }
+            groups.push(format!("{rem:019}"));
+        }
+        groups.reverse();
+        f.pad_integral(true, "", &groups.concat())
+    }
+}
+
+impl<const BYTES: usize> std::fmt::Debug for UInt<BYTES> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl<const BYTES: usize> std::fmt::LowerHex for UInt<BYTES> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = self
+            .0
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        let digits = hex.trim_start_matches('0');
+        f.pad_integral(true, "0x", if digits.is_empty() { "0" } else { digits })
+    }
+}
+
+impl<const BYTES: usize> std::fmt::UpperHex for UInt<BYTES> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = self
+            .0
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<String>();
+        let digits = hex.trim_start_matches('0');
+        f.pad_integral(true, "0x", if digits.is_empty() { "0" } else { digits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U128 = UInt<16>;
+
+    const VALUES: &[u128] = &[
+        0,
+        1,
+        2,
+        3,
+        10,
+        255,
+        256,
+        0xffff_ffff,
+        0x1234_5678_9abc_def0,
+        u64::MAX as u128 + 1,
+        0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+        u128::MAX / 3,
+        u128::MAX - 1,
+        u128::MAX,
+    ];
+
+    #[test]
+    fn test_uint_arith() {
+        for &a in VALUES {
+            for &b in VALUES {
+                let (x, y) = (U128::from(a), U128::from(b));
+                assert_eq!(x + y, U128::from(a.wrapping_add(b)), "{a} + {b}");
+                assert_eq!(x - y, U128::from(a.wrapping_sub(b)), "{a} - {b}");
+                assert_eq!(x * y, U128::from(a.wrapping_mul(b)), "{a} * {b}");
+                assert_eq!(x.checked_add(y), a.checked_add(b).map(U128::from));
+                assert_eq!(x.checked_sub(y), a.checked_sub(b).map(U128::from));
+                assert_eq!(x.checked_mul(y), a.checked_mul(b).map(U128::from));
+                assert_eq!(x.checked_div(y), a.checked_div(b).map(U128::from));
+                assert_eq!(x.checked_rem(y), a.checked_rem(b).map(U128::from));
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                assert_eq!(x & y, U128::from(a & b));
+                assert_eq!(x | y, U128::from(a | b));
+                assert_eq!(x ^ y, U128::from(a ^ b));
+            }
+            let x = U128::from(a);
+            assert_eq!(!x, U128::from(!a));
+            for n in [0, 1, 7, 8, 63, 64, 127] {
+                assert_eq!(x << n, U128::from(a << n));
+                assert_eq!(x >> n, U128::from(a >> n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint_fmt() {
+        for &a in VALUES {
+            let x = U128::from(a);
+            assert_eq!(x.to_string(), a.to_string());
+            assert_eq!(format!("{x:x}"), format!("{a:x}"));
+            assert_eq!(format!("{x:#X}"), format!("{a:#X}"));
+            assert_eq!(format!("{x:>40}"), format!("{a:>40}"));
+        }
+        assert_eq!(
+            U256::MAX.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn test_uint_from() {
+        assert_eq!(
+            U256::try_from(&[0, 0, 1, 2][..]),
+            Ok(U256::from(0x0102_u16))
+        );
+        assert_eq!(
+            UInt::<2>::try_from(&[0, 0, 1, 2][..]),
+            Ok(UInt::<2>::from(0x0102_u16))
+        );
+        assert_eq!(UInt::<2>::try_from(&[0, 1, 1, 2][..]), Err(Error::Overflow));
+        assert_eq!(UInt::<2>::checked_from_u128(1 << 20), None);
+        assert_eq!(U256::from(u64::MAX), U256::from_u64(u64::MAX));
+        assert_eq!(U512::from(7_u8).trailing_zeros(), 0);
+        assert_eq!(U512::from(7_u8).leading_zeros(), 509);
+        assert_eq!(U256::default(), U256::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_uint_div_zero() {
+        let _ = U256::ONE / U256::ZERO;
+    }
+}
@@ -0,0 +1,513 @@
+/**
+ * Variable-length integer codecs.
+ * Each codec is a marker type implementing `VarInt`, it encodes to and decodes from byte slices,
+ * `BitReader` and `BitWriter` streams. The streams may be at any bit position.
+ * - `Leb128`, `Sleb128`: unsigned and signed LEB128 (DWARF, WebAssembly)
+ * - `CompactSize`: Bitcoin's size prefix of 1, 3, 5 or 9 bytes
+ * - `BitcoinVarInt`: Bitcoin Core `VARINT`, MSB base-128 with an offset on each continuation
+ * - `Protobuf`, `ZigZag`: protobuf varints of `uint64` and `sint64`
+ * - `Quic`: QUIC's varint with the length in a 2-bit prefix (RFC 9000)
+ */
+use crate::core::BitOrder;
+use crate::{BitReader, BitWriter, Error, Result};
+use std::io::{self, Read, Write};
+
+/// A variable-length integer codec
+pub trait VarInt {
+    type Value: Copy;
+
+    /// Append the encoded value to the buffer
+    /// # Errors
+    /// - `Error::Overflow` if the value is out of the codec range
+    fn encode(value: Self::Value, out: &mut Vec<u8>) -> Result<()>;
+
+    /// Decode a value from the bytes returned by `next`, `None` is the end of the bytes
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the bytes end before the value
+    /// - `Error::Overflow` if the value does not fit in `Value`
+    /// - `Error::NonCanonical` if the value is not encoded in the shortest form
+    fn decode_with(next: impl FnMut() -> Option<u8>) -> Result<Self::Value>;
+
+    /// Encode the value to a new buffer
+    fn to_vec(value: Self::Value) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        Self::encode(value, &mut out)?;
+        Ok(out)
+    }
+
+    /// Decode a value from the front of the data
+    /// # Returns
+    /// - the value and the number of bytes read
+    fn decode(data: &[u8]) -> Result<(Self::Value, usize)> {
+        let mut bytes = data.iter();
+        let value = Self::decode_with(|| bytes.next().copied())?;
+        Ok((value, data.len() - bytes.len()))
+    }
+
+    /// Read a value from the bit stream
+    /// # Errors
+    /// - the error of the stream, or `InvalidData` with an `Error` of `decode_with`
+    fn read<R: Read, O: BitOrder>(reader: &mut BitReader<R, O>) -> io::Result<Self::Value> {
+        let mut failure = None;
+        let value = Self::decode_with(|| match reader.read_bits::<u8>(8) {
+            Ok(byte) => Some(byte),
+            Err(e) => {
+                failure = Some(e);
+                None
+            }
+        });
+        match (value, failure) {
+            (Ok(value), _) => Ok(value),
+            (Err(_), Some(e)) => Err(e),
+            (Err(e), None) => Err(e.into()),
+        }
+    }
+
+    /// Write a value to the bit stream
+    fn write<W: Write, O: BitOrder>(
+        writer: &mut BitWriter<W, O>,
+        value: Self::Value,
+    ) -> io::Result<()> {
+        let out = Self::to_vec(value)?;
+        out.iter()
+            .try_for_each(|&byte| writer.write_bits(byte as u64, 8))
+    }
+}
+
+/// Unsigned LEB128, 7 bits per byte from the lowest, the high bit marks a continuation
+/// ```
+/// # use xbits::varint::{Leb128, VarInt};
+/// assert_eq!(Leb128::to_vec(624_485).unwrap(), [0xe5, 0x8e, 0x26]);
+/// assert_eq!(Leb128::decode(&[0xe5, 0x8e, 0x26, 0xff]).unwrap(), (624_485, 3));
+/// assert!(Leb128::decode(&[0x80, 0x00]).is_err()); // overlong zero
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Leb128;
+
+impl VarInt for Leb128 {
+    type Value = u64;
+
+    fn encode(value: u64, out: &mut Vec<u8>) -> Result<()> {
+        let mut value = value;
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+        Ok(())
+    }
+
+    fn decode_with(next: impl FnMut() -> Option<u8>) -> Result<u64> {
+        let (value, last, len) = decode_groups(next)?;
+        match len > 1 && last == 0 {
+            true => Err(Error::NonCanonical),
+            false => Ok(value),
+        }
+    }
+}
+
+/// Signed LEB128, the last byte is sign-extended from its bit 6
+/// ```
+/// # use xbits::varint::{Sleb128, VarInt};
+/// assert_eq!(Sleb128::to_vec(-123_456).unwrap(), [0xc0, 0xbb, 0x78]);
+/// assert_eq!(Sleb128::decode(&[0x7f]).unwrap(), (-1, 1));
+/// assert!(Sleb128::decode(&[0xff, 0x7f]).is_err()); // overlong -1
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Sleb128;
+
+impl VarInt for Sleb128 {
+    type Value = i64;
+
+    fn encode(value: i64, out: &mut Vec<u8>) -> Result<()> {
+        let mut value = value;
+        loop {
+            let byte = value as u8 & 0x7f;
+            value >>= 7;
+            let sign = byte & 0x40 != 0;
+            if (value == 0 && !sign) || (value == -1 && sign) {
+                out.push(byte);
+                return Ok(());
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn decode_with(mut next: impl FnMut() -> Option<u8>) -> Result<i64> {
+        let (mut value, mut prev) = (0_i64, 0_u8);
+        for shift in (0..i64::BITS).step_by(7) {
+            let byte = next().ok_or(Error::UnexpectedEnd)?;
+            if shift == 63 && byte != 0x00 && byte != 0x7f {
+                return Err(Error::Overflow); // only the sign bits are left
+            }
+            value |= ((byte & 0x7f) as i64) << shift;
+            if byte & 0x80 != 0 {
+                prev = byte;
+                continue;
+            }
+            // the last byte only extends the sign of the previous one
+            let sign = byte & 0x40 != 0;
+            if shift > 0 && byte == if prev & 0x40 != 0 { 0x7f } else { 0x00 } {
+                return Err(Error::NonCanonical);
+            }
+            if sign && shift + 7 < i64::BITS {
+                value |= -1 << (shift + 7);
+            }
+            return Ok(value);
+        }
+        Err(Error::Overflow)
+    }
+}
+
+/// Bitcoin's `CompactSize`, a byte below `0xfd`, or `0xfd`, `0xfe`, `0xff`
+/// followed by a little-endian `u16`, `u32` or `u64`
+/// ```
+/// # use xbits::varint::{CompactSize, VarInt};
+/// assert_eq!(CompactSize::to_vec(0xfc).unwrap(), [0xfc]);
+/// assert_eq!(CompactSize::to_vec(0xfd).unwrap(), [0xfd, 0xfd, 0x00]);
+/// assert!(CompactSize::decode(&[0xfd, 0xfc, 0x00]).is_err()); // fits in 1 byte
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CompactSize;
+
+impl VarInt for CompactSize {
+    type Value = u64;
+
+    fn encode(value: u64, out: &mut Vec<u8>) -> Result<()> {
+        match value {
+            0..0xfd => out.push(value as u8),
+            0xfd..=0xffff => {
+                out.push(0xfd);
+                out.extend((value as u16).to_le_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                out.push(0xfe);
+                out.extend((value as u32).to_le_bytes());
+            }
+            _ => {
+                out.push(0xff);
+                out.extend(value.to_le_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn decode_with(mut next: impl FnMut() -> Option<u8>) -> Result<u64> {
+        let (len, min) = match next().ok_or(Error::UnexpectedEnd)? {
+            prefix @ 0..0xfd => return Ok(prefix as u64),
+            0xfd => (2, 0xfd),
+            0xfe => (4, 0x1_0000),
+            _ => (8, 0x1_0000_0000),
+        };
+        let mut bytes = [0; 8];
+        for byte in &mut bytes[..len] {
+            *byte = next().ok_or(Error::UnexpectedEnd)?;
+        }
+        match u64::from_le_bytes(bytes) {
+            value if value < min => Err(Error::NonCanonical),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Bitcoin Core's `VARINT`, 7 bits per byte from the highest, the high bit marks a continuation.
+/// Each continuation adds one to the value, so every value has exactly one encoding.
+/// ```
+/// # use xbits::varint::{BitcoinVarInt, VarInt};
+/// assert_eq!(BitcoinVarInt::to_vec(0x80).unwrap(), [0x80, 0x00]);
+/// assert_eq!(BitcoinVarInt::to_vec(0xffff).unwrap(), [0x82, 0xfe, 0x7f]);
+/// assert_eq!(BitcoinVarInt::decode(&[0x82, 0xfe, 0x7f]).unwrap(), (0xffff, 3));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BitcoinVarInt;
+
+impl VarInt for BitcoinVarInt {
+    type Value = u64;
+
+    fn encode(value: u64, out: &mut Vec<u8>) -> Result<()> {
+        let mut bytes = [0; 10];
+        let (mut value, mut i) = (value, bytes.len() - 1);
+        bytes[i] = value as u8 & 0x7f;
+        while value > 0x7f {
+            value = (value >> 7) - 1;
+            i -= 1;
+            bytes[i] = value as u8 | 0x80;
+        }
+        out.extend(&bytes[i..]);
+        Ok(())
+    }
+
+    fn decode_with(mut next: impl FnMut() -> Option<u8>) -> Result<u64> {
+        let mut value = 0_u64;
+        loop {
+            let byte = next().ok_or(Error::UnexpectedEnd)?;
+            if value > u64::MAX >> 7 {
+                return Err(Error::Overflow);
+            }
+            value = (value << 7) | (byte & 0x7f) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            value = value.checked_add(1).ok_or(Error::Overflow)?;
+        }
+    }
+}
+
+/// Protobuf varint of `uint64`, the same bytes as `Leb128`.
+/// The decoder accepts the overlong encodings of at most 10 bytes like the protobuf parsers.
+/// ```
+/// # use xbits::varint::{Protobuf, VarInt};
+/// assert_eq!(Protobuf::to_vec(150).unwrap(), [0x96, 0x01]);
+/// assert_eq!(Protobuf::decode(&[0x96, 0x81, 0x00]).unwrap(), (150, 3));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Protobuf;
+
+impl VarInt for Protobuf {
+    type Value = u64;
+
+    fn encode(value: u64, out: &mut Vec<u8>) -> Result<()> {
+        Leb128::encode(value, out)
+    }
+
+    fn decode_with(next: impl FnMut() -> Option<u8>) -> Result<u64> {
+        decode_groups(next).map(|(value, _, _)| value)
+    }
+}
+
+/// Protobuf varint of `sint64`, the zigzag mapped value in a `Protobuf` varint
+/// ```
+/// # use xbits::varint::{VarInt, ZigZag};
+/// assert_eq!(ZigZag::to_vec(-1).unwrap(), [0x01]);
+/// assert_eq!(ZigZag::to_vec(64).unwrap(), [0x80, 0x01]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ZigZag;
+
+impl VarInt for ZigZag {
+    type Value = i64;
+
+    fn encode(value: i64, out: &mut Vec<u8>) -> Result<()> {
+        Protobuf::encode(zigzag_encode(value), out)
+    }
+
+    fn decode_with(next: impl FnMut() -> Option<u8>) -> Result<i64> {
+        Protobuf::decode_with(next).map(zigzag_decode)
+    }
+}
+
+/// Map a signed value to unsigned, the small magnitudes to the small values:
+/// `0, -1, 1, -2, 2` to `0, 1, 2, 3, 4`
+/// ```
+/// # use xbits::varint::zigzag_encode;
+/// assert_eq!(zigzag_encode(-2), 3);
+/// assert_eq!(zigzag_encode(i64::MIN), u64::MAX);
+/// ```
+#[inline(always)]
+pub fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Map a zigzag value back to signed
+/// ```
+/// # use xbits::varint::zigzag_decode;
+/// assert_eq!(zigzag_decode(3), -2);
+/// ```
+#[inline(always)]
+pub fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// QUIC's varint, the highest 2 bits of the first byte are the length of 1, 2, 4 or 8 bytes,
+/// the other bits are the big-endian value of at most 62 bits.
+/// The decoder accepts the encodings longer than necessary, RFC 9000 allows them.
+/// ```
+/// # use xbits::varint::{Quic, VarInt};
+/// assert_eq!(Quic::to_vec(15_293).unwrap(), [0x7b, 0xbd]);
+/// assert_eq!(Quic::decode(&[0x40, 0x25]).unwrap(), (37, 2));
+/// assert!(Quic::to_vec(1 << 62).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Quic;
+
+impl VarInt for Quic {
+    type Value = u64;
+
+    fn encode(value: u64, out: &mut Vec<u8>) -> Result<()> {
+        match value {
+            0..0x40 => out.push(value as u8),
+            0x40..0x4000 => out.extend((value as u16 | 0x4000).to_be_bytes()),
+            0x4000..0x4000_0000 => out.extend((value as u32 | 0x8000_0000).to_be_bytes()),
+            0x4000_0000..0x4000_0000_0000_0000 => out.extend((value | 0xc0 << 56).to_be_bytes()),
+            _ => return Err(Error::Overflow),
+        }
+        Ok(())
+    }
+
+    fn decode_with(mut next: impl FnMut() -> Option<u8>) -> Result<u64> {
+        let first = next().ok_or(Error::UnexpectedEnd)?;
+        let mut value = (first & 0x3f) as u64;
+        for _ in 1..1 << (first >> 6) {
+            value = (value << 8) | next().ok_or(Error::UnexpectedEnd)? as u64;
+        }
+        Ok(value)
+    }
+}
+
+/// Decode the 7-bit groups from the lowest of at most 10 bytes
+/// # Returns
+/// - the value, the last byte and the number of bytes
+fn decode_groups(mut next: impl FnMut() -> Option<u8>) -> Result<(u64, u8, usize)> {
+    let mut value = 0_u64;
+    for (i, shift) in (0..u64::BITS).step_by(7).enumerate() {
+        let byte = next().ok_or(Error::UnexpectedEnd)?;
+        if shift == 63 && byte > 1 {
+            return Err(Error::Overflow);
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, byte, i + 1));
+        }
+    }
+    unreachable!("the 10th byte has no continuation")
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+
+    fn values() -> impl Iterator<Item = u64> {
+        let edges = (0..64).flat_map(|i| [(1 << i) - 1, 1 << i, (1 << i) + 1]);
+        edges.chain([0xfc, 0xfd, 0xffff, 0x1_0000, u64::MAX - 1, u64::MAX])
+    }
+
+    fn round_trip<C: VarInt>(value: C::Value)
+    where
+        C::Value: PartialEq + std::fmt::Debug,
+    {
+        let bytes = C::to_vec(value).unwrap();
+        assert_eq!(C::decode(&bytes), Ok((value, bytes.len())));
+        for end in 0..bytes.len() {
+            assert_eq!(C::decode(&bytes[..end]), Err(Error::UnexpectedEnd));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for value in values() {
+            round_trip::<Leb128>(value);
+            round_trip::<Sleb128>(value as i64);
+            round_trip::<Sleb128>((value as i64).wrapping_neg());
+            round_trip::<CompactSize>(value);
+            round_trip::<BitcoinVarInt>(value);
+            round_trip::<Protobuf>(value);
+            round_trip::<ZigZag>(value as i64);
+            if value < 1 << 62 {
+                round_trip::<Quic>(value);
+            }
+            assert_eq!(zigzag_decode(zigzag_encode(value as i64)), value as i64);
+        }
+        round_trip::<Sleb128>(i64::MIN);
+        round_trip::<ZigZag>(i64::MIN);
+        assert_eq!(Leb128::to_vec(u64::MAX).unwrap().len(), 10);
+        assert_eq!(Sleb128::to_vec(i64::MIN).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Bitcoin Core serialize_tests
+        let vectors: [(u64, &str); 9] = [
+            (0, "00"),
+            (0x7f, "7f"),
+            (0x80, "8000"),
+            (0x1234, "a334"),
+            (0xffff, "82fe7f"),
+            (0x123456, "c7e756"),
+            (0x80123456, "86ffc7e756"),
+            (0xffffffff, "8efefefe7f"),
+            (u64::MAX, "80fefefefefefefefe7f"),
+        ];
+        for (value, bytes) in vectors {
+            assert_eq!(hex::encode(BitcoinVarInt::to_vec(value).unwrap()), bytes);
+        }
+
+        // RFC 9000 appendix A.1
+        let vectors: [(u64, &str); 4] = [
+            (151_288_809_941_952_652, "c2197c5eff14e88c"),
+            (494_878_333, "9d7f3e7d"),
+            (15_293, "7bbd"),
+            (37, "25"),
+        ];
+        for (value, bytes) in vectors {
+            assert_eq!(hex::encode(Quic::to_vec(value).unwrap()), bytes);
+        }
+
+        assert_eq!(
+            CompactSize::to_vec(0x1_0000_0000).unwrap(),
+            [0xff, 0, 0, 0, 0, 1, 0, 0, 0]
+        );
+        assert_eq!(Sleb128::to_vec(63).unwrap(), [0x3f]);
+        assert_eq!(Sleb128::to_vec(64).unwrap(), [0xc0, 0x00]);
+        assert_eq!(Sleb128::to_vec(-64).unwrap(), [0x40]);
+        assert_eq!(Sleb128::to_vec(-65).unwrap(), [0xbf, 0x7f]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(Leb128::decode(&[0xff, 0x00]), Err(Error::NonCanonical));
+        assert_eq!(Protobuf::decode(&[0xff, 0x00]), Ok((0x7f, 2)));
+        let mut overflow = [0xff; 10];
+        overflow[9] = 0x02;
+        assert_eq!(Leb128::decode(&overflow), Err(Error::Overflow));
+        assert_eq!(Protobuf::decode(&overflow), Err(Error::Overflow));
+        assert_eq!(Protobuf::decode(&[0x80; 11]), Err(Error::Overflow));
+
+        assert_eq!(Sleb128::decode(&[0xc0, 0x00]), Ok((64, 2)));
+        assert_eq!(Sleb128::decode(&[0x80, 0x00]), Err(Error::NonCanonical));
+        assert_eq!(Sleb128::decode(&[0xc0, 0x7f]), Err(Error::NonCanonical));
+        let mut overflow = [0x80; 10];
+        overflow[9] = 0x01;
+        assert_eq!(Sleb128::decode(&overflow), Err(Error::Overflow));
+
+        assert_eq!(
+            CompactSize::decode(&[0xfe, 0xff, 0xff, 0, 0]),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            CompactSize::decode(&[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            BitcoinVarInt::decode(&[0x80, 0xfe, 0xfe, 0xfe, 0xfe, 0xfe, 0xfe, 0xfe, 0xff, 0x00]),
+            Err(Error::Overflow)
+        );
+        assert_eq!(BitcoinVarInt::decode(&[0x81; 11]), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut writer = BitWriter::new(vec![]);
+        writer.write_bit(true).unwrap();
+        Leb128::write(&mut writer, 300).unwrap();
+        ZigZag::write(&mut writer, -300).unwrap();
+        Quic::write(&mut writer, 494_878_333).unwrap();
+        CompactSize::write(&mut writer, 0xfd).unwrap();
+        assert!(Quic::write(&mut writer, u64::MAX).is_err());
+        let (data, bits) = writer.into_inner().unwrap();
+        assert_eq!(bits, 1 + 8 * (2 + 2 + 4 + 3));
+
+        let mut reader = BitReader::new(&data[..]);
+        assert_eq!(reader.read_bit().unwrap(), true);
+        assert_eq!(Leb128::read(&mut reader).unwrap(), 300);
+        assert_eq!(ZigZag::read(&mut reader).unwrap(), -300);
+        assert_eq!(Quic::read(&mut reader).unwrap(), 494_878_333);
+        assert_eq!(CompactSize::read(&mut reader).unwrap(), 0xfd);
+        let err = Leb128::read(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut reader = BitReader::new(&[0x80, 0x00][..]);
+        let err = Leb128::read(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,446 @@
+/**
+ * `AsWordBits` and `AsWordBitsMut` trait provides bit-level views over unsigned word slices.
+ * The words are read as a stream of bytes in the word endianness without copying,
+ * so the big-endian view of `[u32]` gives the same bits as its big-endian bytes.
+ */
+use super::core::{BitOrder, Msb0};
+use crate::assert_range;
+use std::marker::PhantomData;
+
+/// Unsigned storage word of a bit view
+pub trait Word:
+    std::fmt::Debug + Copy + Default + Eq + std::ops::Not<Output = Self> + sealed::Bytes
+{
+}
+
+/// Byte order of the words in a bit view
+pub trait Endian: std::fmt::Debug + Copy + Default + Eq + sealed::Layout {}
+
+/// Big-endian words, the most significant byte of a word first. The default endianness.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Be;
+
+/// Little-endian words, the least significant byte of a word first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Le;
+
+impl Endian for Be {}
+impl Endian for Le {}
+
+mod sealed {
+    pub trait Bytes {
+        /// Number of bytes in a word
+        const BYTES: usize;
+
+        /// The word of all zero bits
+        const ZERO: Self;
+
+        /// The word of all one bits
+        const MAX: Self;
+
+        /// Get the byte at big-endian index `i`
+        fn byte(self, i: usize) -> u8;
+
+        /// Set the byte at big-endian index `i`
+        fn set_byte(&mut self, i: usize, value: u8);
+    }
+
+    pub trait Layout {
+        /// Map the stream index `i` of a byte in a word to the big-endian index
+        fn index(i: usize, bytes: usize) -> usize;
+    }
+}
+
+impl sealed::Layout for Be {
+    #[inline(always)]
+    fn index(i: usize, _bytes: usize) -> usize {
+        i
+    }
+}
+
+impl sealed::Layout for Le {
+    #[inline(always)]
+    fn index(i: usize, bytes: usize) -> usize {
+        bytes - 1 - i
+    }
+}
+
+macro_rules! impl_word {
+    ($t:ty) => {
+        impl Word for $t {}
+
+        impl sealed::Bytes for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            const ZERO: Self = 0;
+            const MAX: Self = <$t>::MAX;
+
+            #[inline(always)]
+            fn byte(self, i: usize) -> u8 {
+                (self >> ((Self::BYTES - 1 - i) * 8)) as u8
+            }
+
+            #[inline(always)]
+            fn set_byte(&mut self, i: usize, value: u8) {
+                let shift = (Self::BYTES - 1 - i) * 8;
+                *self = (*self & !((0xff as $t) << shift)) | ((value as $t) << shift);
+            }
+        }
+
+        impl AsWordBits for [$t] {
+            type Word = $t;
+
+            #[inline(always)]
+            fn as_bits_in<E: Endian, O: BitOrder>(&self) -> WordsRef<'_, $t, E, O> {
+                WordsRef::new(self)
+            }
+        }
+
+        impl AsWordBitsMut for [$t] {
+            type Word = $t;
+
+            #[inline(always)]
+            fn as_bits_mut_in<E: Endian, O: BitOrder>(&mut self) -> WordsMut<'_, $t, E, O> {
+                WordsMut::new(self)
+            }
+        }
+    };
+}
+
+impl_word!(u16);
+impl_word!(u32);
+impl_word!(u64);
+impl_word!(u128);
+
+pub trait AsWordBits {
+    type Word: Word;
+
+    /// View the bits of big-endian words
+    /// # Examples
+    /// ```
+    /// # use xbits::AsWordBits;
+    /// let state = [0x0123_4567_u32, 0x89ab_cdef];
+    /// assert_eq!(state.as_bits().bytes().collect::<Vec<_>>(), 0x0123_4567_89ab_cdef_u64.to_be_bytes());
+    /// ```
+    #[inline(always)]
+    fn as_bits(&self) -> WordsRef<'_, Self::Word> {
+        self.as_bits_in::<Be, Msb0>()
+    }
+
+    /// View the bits of little-endian words
+    /// # Examples
+    /// ```
+    /// # use xbits::AsWordBits;
+    /// let state = [0x0123_u16, 0x4567];
+    /// assert_eq!(state.as_bits_le().bytes().collect::<Vec<_>>(), [0x23, 0x01, 0x67, 0x45]);
+    /// ```
+    #[inline(always)]
+    fn as_bits_le(&self) -> WordsRef<'_, Self::Word, Le> {
+        self.as_bits_in::<Le, Msb0>()
+    }
+
+    /// View the bits of words in the endianness `E` and the bit order `O`
+    fn as_bits_in<E: Endian, O: BitOrder>(&self) -> WordsRef<'_, Self::Word, E, O>;
+}
+
+pub trait AsWordBitsMut {
+    type Word: Word;
+
+    /// View the mutable bits of big-endian words
+    #[inline(always)]
+    fn as_bits_mut(&mut self) -> WordsMut<'_, Self::Word> {
+        self.as_bits_mut_in::<Be, Msb0>()
+    }
+
+    /// View the mutable bits of little-endian words
+    #[inline(always)]
+    fn as_bits_mut_le(&mut self) -> WordsMut<'_, Self::Word, Le> {
+        self.as_bits_mut_in::<Le, Msb0>()
+    }
+
+    /// View the mutable bits of words in the endianness `E` and the bit order `O`
+    fn as_bits_mut_in<E: Endian, O: BitOrder>(&mut self) -> WordsMut<'_, Self::Word, E, O>;
+}
+
+/// A wrapper to a word slice that allows for bit-level operations without copying.
+/// The bits are indexed through the bytes of each word in the endianness `E`,
+/// and through the bits of each byte in the bit order `O`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordsRef<'a, W: Word, E: Endian = Be, O: BitOrder = Msb0>(
+    pub &'a [W],
+    PhantomData<(E, O)>,
+);
+
+impl<'a, W: Word, E: Endian, O: BitOrder> WordsRef<'a, W, E, O> {
+    #[inline(always)]
+    pub const fn new(words: &'a [W]) -> Self {
+        WordsRef(words, PhantomData)
+    }
+
+    #[inline(always)]
+    pub fn as_words(&self) -> &[W] {
+        self.0
+    }
+
+    /// Returns true if the word slice is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of bits in the word slice.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.len() * W::BYTES * 8
+    }
+
+    /// Get the byte at index `i` of the byte stream
+    #[inline(always)]
+    pub fn byte(&self, i: usize) -> u8 {
+        self.0[i / W::BYTES].byte(E::index(i % W::BYTES, W::BYTES))
+    }
+
+    /// Iterator the byte stream of the words
+    #[inline(always)]
+    pub fn bytes(&self) -> impl DoubleEndedIterator<Item = u8> + '_ {
+        self.0
+            .iter()
+            .flat_map(|&w| (0..W::BYTES).map(move |i| w.byte(E::index(i, W::BYTES))))
+    }
+
+    /// Copy the byte stream of the words
+    #[inline(always)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes().collect()
+    }
+
+    #[inline(always)]
+    pub fn all_one(&self) -> bool {
+        self.bytes().all(|b| b == 0xff)
+    }
+
+    #[inline(always)]
+    pub fn all_zero(&self) -> bool {
+        self.0.iter().all(|&w| w == W::default())
+    }
+
+    #[inline(always)]
+    pub fn leading_zeros(&self) -> usize {
+        match (0..self.len() / 8).find(|&i| self.byte(i) != 0) {
+            Some(n) => n * 8 + self.byte(n).leading_zeros() as usize,
+            None => self.len(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn trailing_zeros(&self) -> usize {
+        match (0..self.len() / 8).rfind(|&i| self.byte(i) != 0) {
+            Some(n) => self.len() - (n + 1) * 8 + self.byte(n).trailing_zeros() as usize,
+            None => self.len(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> bool {
+        self.byte(index / 8) & O::mask(index % 8) != 0
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.bytes()
+            .flat_map(|b| (0..8).map(move |n| b & O::mask(n) != 0))
+    }
+
+    /// Returns the bits grouped by n, the same as `bit_chunks_in` on the byte stream
+    ///
+    /// # Parameters
+    /// - `T`: the type to contains the grouped bits
+    /// - `n`: the number of bits to group
+    /// - 1 <= n <= T::BITS <= 64
+    ///
+    /// # Examples
+    /// ```
+    /// # use xbits::AsWordBits;
+    /// let state = [0xffff_ffff_u32; 2];
+    /// assert_eq!(
+    ///     state.as_bits().chunks::<u16>(11).collect::<Vec<_>>(),
+    ///     [0x7ff, 0x7ff, 0x7ff, 0x7ff, 0x7ff, 0b111_1111_1100]
+    /// );
+    /// ```
+    pub fn chunks<T>(&self, n: usize) -> impl Iterator<Item = T> + '_
+    where
+        T: TryFrom<u64> + Default + 'static,
+    {
+        let valid_size = (std::mem::size_of::<T>() * 8).min(64);
+        assert_range!(n, 1, valid_size, "chunks");
+
+        // cache the bytes of the stream, split the grouped bits from the cache
+        let mut bytes = self.bytes();
+        let (mut cache, mut len) = (0_u128, 0);
+        std::iter::from_fn(move || {
+            while len < n {
+                match bytes.next() {
+                    Some(b) => (cache, len) = (O::push(cache, len, b as u64, 8), len + 8),
+                    None if len == 0 => return None,
+                    None => (cache, len) = (O::push(cache, len, 0, n - len), n), // tail padding zero bits
+                }
+            }
+            let (value, rest) = O::pop(cache, len, n);
+            (cache, len) = (rest, len - n);
+            Some(value.try_into().unwrap_or_default())
+        })
+    }
+}
+
+impl<W: Word, E: Endian, O: BitOrder> std::ops::Index<usize> for WordsRef<'_, W, E, O> {
+    type Output = bool;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        if self.get(index) { &true } else { &false }
+    }
+}
+
+impl<W: Word, E: Endian, O: BitOrder> std::fmt::Display for WordsRef<'_, W, E, O> {
+    /// Format the bits of each byte in the bit order
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.bytes().map(|b| {
+            (0..8)
+                .map(|n| if b & O::mask(n) != 0 { '1' } else { '0' })
+                .collect::<String>()
+        });
+        write!(f, "{}", bytes.collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// A mutable wrapper to a word slice that allows for bit-level operations without copying.
+#[derive(Debug, PartialEq, Eq)]
+pub struct WordsMut<'a, W: Word, E: Endian = Be, O: BitOrder = Msb0>(
+    pub &'a mut [W],
+    PhantomData<(E, O)>,
+);
+
+impl<'a, W: Word, E: Endian, O: BitOrder> WordsMut<'a, W, E, O> {
+    #[inline(always)]
+    pub const fn new(words: &'a mut [W]) -> Self {
+        WordsMut(words, PhantomData)
+    }
+
+    #[inline(always)]
+    pub fn to_ref(&self) -> WordsRef<'_, W, E, O> {
+        WordsRef::new(self.0)
+    }
+
+    #[inline(always)]
+    pub fn set(self, index: usize, value: bool) -> Self {
+        let (i, mask) = (index / 8, O::mask(index % 8));
+        let word = &mut self.0[i / W::BYTES];
+        let j = E::index(i % W::BYTES, W::BYTES);
+        let byte = word.byte(j);
+        word.set_byte(j, if value { byte | mask } else { byte & !mask });
+        self
+    }
+
+    #[inline(always)]
+    pub fn fill(self, value: bool) -> Self {
+        // every bit of the words is the same, in any endianness and bit order
+        self.0.fill(if value { W::MAX } else { W::ZERO });
+        self
+    }
+
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        self.0.iter_mut().for_each(|w| *w = !*w);
+        self
+    }
+}
+
+impl<W: Word, E: Endian, O: BitOrder> std::fmt::Display for WordsMut<'_, W, E, O> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_ref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use crate::core::{BitIterator, Lsb0};
+    use crate::{AsBits, ToBits};
+
+    const STATE: [u32; 8] = [
+        0x6a09_e667,
+        0xbb67_ae85,
+        0x3c6e_f372,
+        0xa54f_f53a,
+        0x510e_527f,
+        0x9b05_688c,
+        0x1f83_d9ab,
+        0x5be0_cd19,
+    ];
+
+    #[test]
+    fn test_words_bytes() {
+        let bytes = STATE.to_vec().to_bits();
+        assert_eq!(STATE.as_bits().to_bytes(), bytes);
+        assert_eq!(STATE.as_bits().len(), 256);
+        assert_eq!(STATE.as_bits().to_string(), bytes.as_bits().to_string());
+
+        let le: Vec<u8> = STATE.iter().flat_map(|w| w.to_le_bytes()).collect();
+        assert_eq!(STATE.as_bits_le().to_bytes(), le);
+        let wide = [u128::MAX - 1, 0x1234];
+        assert_eq!(wide.as_bits().to_bytes(), wide.to_vec().to_bits());
+    }
+
+    #[test]
+    fn test_words_chunks() {
+        let bytes = STATE.to_vec().to_bits();
+        for n in [1, 5, 6, 11, 17, 32] {
+            assert_eq!(
+                STATE.as_bits().chunks::<u64>(n).collect::<Vec<_>>(),
+                bytes.bit_chunks::<u64>(n).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                STATE
+                    .as_bits_in::<Be, Lsb0>()
+                    .chunks::<u64>(n)
+                    .collect::<Vec<_>>(),
+                bytes.bit_chunks_in::<Lsb0, u64>(n).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(
+            STATE.as_bits().iter().collect::<Vec<_>>(),
+            bytes.bit_iter().collect::<Vec<_>>()
+        );
+        assert_eq!([0_u64; 0].as_bits().chunks::<u8>(3).count(), 0);
+    }
+
+    #[test]
+    fn test_words_zeros() {
+        let words = [0_u16, 0x0100, 0x0080];
+        assert_eq!(words.as_bits().leading_zeros(), 23);
+        assert_eq!(words.as_bits().trailing_zeros(), 7);
+        assert_eq!(words.as_bits_le().leading_zeros(), 31);
+        assert_eq!(words.as_bits_le().trailing_zeros(), 15);
+        assert_eq!([0_u32; 2].as_bits().leading_zeros(), 64);
+        assert_eq!([0_u32; 2].as_bits().all_zero(), true);
+        assert_eq!([u64::MAX].as_bits().all_one(), true);
+    }
+
+    #[test]
+    fn test_words_mut() {
+        let mut words = [0_u32; 2];
+        words.as_bits_mut().set(0, true).set(63, true);
+        assert_eq!(words, [0x8000_0000, 0x0000_0001]);
+        words.as_bits_mut_le().set(0, true);
+        assert_eq!(words, [0x8000_0080, 0x0000_0001]);
+        words
+            .as_bits_mut_in::<Le, Lsb0>()
+            .set(31, false)
+            .set(8, true);
+        assert_eq!(words, [0x0000_0180, 0x0000_0001]);
+        assert_eq!(words.as_bits_mut().not().to_ref()[0], true);
+        assert_eq!(words.as_bits_mut().fill(false).to_ref().all_zero(), true);
+    }
+}
@@ -3,51 +3,92 @@
  * It allows you to get a reference to the bits in a byte array and perform operations
  * such as checking if all bits are one or zero, and iterating over the bits.
  */
-use super::core::{BitIterator, Bitwise};
+use super::core::{BitField, BitIterator, BitOrder, Bitwise, Msb0, Positions, Runs};
+use crate::{RangeMut, RangeRef, Result};
+use std::marker::PhantomData;
 
 pub trait AsBits {
-    fn as_bits(&self) -> BitsRef;
+    /// The bit order of the view, `Msb0` for the byte arrays
+    type Order: BitOrder;
+
+    fn as_bits(&self) -> BitsRef<'_, Self::Order>;
+
+    /// View the bits in the bit order `O`
+    /// # Examples
+    /// ```
+    /// # use xbits::{AsBits, Lsb0};
+    /// assert_eq!([0b0000_0001].as_bits_in::<Lsb0>()[0], true);
+    /// ```
+    #[inline(always)]
+    fn as_bits_in<O: BitOrder>(&self) -> BitsRef<'_, O> {
+        BitsRef::new(self.as_bits().0)
+    }
 }
 
-pub trait AsBitsMut {
-    fn as_bits_mut(&mut self) -> BitsMut;
+pub trait AsBitsMut: AsBits {
+    fn as_bits_mut(&mut self) -> BitsMut<'_, Self::Order>;
+
+    /// View the mutable bits in the bit order `O`
+    #[inline(always)]
+    fn as_bits_mut_in<O: BitOrder>(&mut self) -> BitsMut<'_, O> {
+        BitsMut::new(self.as_bits_mut().0)
+    }
 }
 
 impl AsBits for [u8] {
+    type Order = Msb0;
+
     #[inline(always)]
-    fn as_bits(&self) -> BitsRef {
-        BitsRef(self)
+    fn as_bits(&self) -> BitsRef<'_> {
+        BitsRef::new(self)
     }
 }
 
 impl AsBitsMut for [u8] {
     #[inline(always)]
-    fn as_bits_mut(&mut self) -> BitsMut {
-        BitsMut(self)
+    fn as_bits_mut(&mut self) -> BitsMut<'_> {
+        BitsMut::new(self)
     }
 }
 
 impl AsBits for Vec<u8> {
+    type Order = Msb0;
+
     #[inline(always)]
-    fn as_bits(&self) -> BitsRef {
-        BitsRef(self)
+    fn as_bits(&self) -> BitsRef<'_> {
+        BitsRef::new(self)
     }
 }
 
 impl AsBitsMut for Vec<u8> {
     #[inline(always)]
-    fn as_bits_mut(&mut self) -> BitsMut {
-        BitsMut(self)
+    fn as_bits_mut(&mut self) -> BitsMut<'_> {
+        BitsMut::new(self)
     }
 }
 
 /// A wrapper to a byte array that allows for bit-level operations.
 /// It provides easy to use methods for checking bit states, iterating over bits,
 /// and performing bitwise operations.
+/// The bit order `O` applies to indexing, iterating and chunking,
+/// the counting of leading and trailing zeros works on the big-endian value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct BitsRef<'a>(pub &'a [u8]);
+pub struct BitsRef<'a, O: BitOrder = Msb0>(pub &'a [u8], pub PhantomData<O>);
+
+impl<'a, O: BitOrder> BitsRef<'a, O> {
+    /// Create a view of the bytes, it replaces the tuple constructor `BitsRef(data)`
+    /// # Examples
+    /// ```
+    /// # use xbits::{BitsRef, Lsb0};
+    /// const BITS: BitsRef = BitsRef::new(&[0b1000_0000]);
+    /// assert_eq!(BITS.get(0), true);
+    /// assert_eq!(BitsRef::<Lsb0>::new(&[0b1000_0000]).get(7), true);
+    /// ```
+    #[inline(always)]
+    pub const fn new(data: &'a [u8]) -> Self {
+        BitsRef(data, PhantomData)
+    }
 
-impl BitsRef<'_> {
     #[inline(always)]
     pub fn as_bytes(&self) -> &[u8] {
         self.0
@@ -80,14 +121,41 @@ impl BitsRef<'_> {
         self.0.bit_leading_zeros()
     }
 
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        self.0.bit_count_ones()
+    }
+
+    #[inline(always)]
+    pub fn count_zeros(&self) -> usize {
+        self.0.bit_count_zeros()
+    }
+
+    /// Returns true if the number of one bits is odd
+    #[inline(always)]
+    pub fn parity(&self) -> bool {
+        self.0.bit_parity()
+    }
+
+    /// Count the number of different bits, the shorter one is extended with leading zeros
+    #[inline(always)]
+    pub fn hamming_distance(&self, other: impl AsBits) -> usize {
+        self.0.bit_hamming_distance(other.as_bits().0)
+    }
+
     #[inline(always)]
     pub fn trailing_zeros(&self) -> usize {
         self.0.bit_trailing_zeros()
     }
 
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> bool {
+        self.0.bit_get_in::<O>(index)
+    }
+
     #[inline(always)]
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
-        self.0.bit_iter()
+        self.0.bit_iter_in::<O>()
     }
 
     #[inline(always)]
@@ -95,42 +163,142 @@ impl BitsRef<'_> {
     where
         T: TryFrom<u64> + Default + 'static,
     {
-        self.0.bit_chunks(n)
+        self.0.bit_chunks_in::<O, T>(n)
+    }
+
+    /// Iterator the indexes of the one bits, skipping whole zero words
+    /// # Examples
+    /// ```
+    /// # use xbits::AsBits;
+    /// let data = [0b1000_0000, 0b0100_0001];
+    /// assert_eq!(data.as_bits().ones().collect::<Vec<_>>(), [0, 9, 15]);
+    /// ```
+    #[inline(always)]
+    pub fn ones(&self) -> Positions<'a, O> {
+        Positions::new(self.0, 0..self.len(), true)
+    }
+
+    /// Iterator the indexes of the zero bits, skipping whole one words
+    #[inline(always)]
+    pub fn zeros(&self) -> Positions<'a, O> {
+        Positions::new(self.0, 0..self.len(), false)
+    }
+
+    /// Iterator the runs of the same bits as `(value, start, len)`
+    /// # Examples
+    /// ```
+    /// # use xbits::{AsBits, Lsb0};
+    /// assert_eq!(
+    ///     [0b0000_0111].as_bits_in::<Lsb0>().runs().collect::<Vec<_>>(),
+    ///     [(true, 0, 3), (false, 3, 5)]
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn runs(&self) -> Runs<'a, O> {
+        Runs::new(self.0, 0..self.len())
+    }
+
+    /// Index of the first one bit
+    #[inline(always)]
+    pub fn first_one(&self) -> Option<usize> {
+        self.ones().next()
     }
 
-    // pub fn range(&self, range: std::ops::Range<usize>) -> RangeRef<'_> {
-    //     RangeRef {
-    //         data: self.0,
-    //         range,
-    //     }
-    // }
+    /// Index of the last one bit
+    #[inline(always)]
+    pub fn last_one(&self) -> Option<usize> {
+        self.ones().next_back()
+    }
+
+    /// Index of the first one bit after the index `i`
+    #[inline(always)]
+    pub fn next_one_after(&self, i: usize) -> Option<usize> {
+        let len = self.len();
+        Positions::<O>::new(self.0, i.saturating_add(1).min(len)..len, true).next()
+    }
+
+    /// Index of the last one bit before the index `i`
+    /// # Examples
+    /// ```
+    /// # use xbits::{AsBits, Lsb0};
+    /// let bits = [0b0000_0101].as_bits_in::<Lsb0>();
+    /// assert_eq!(bits.prev_one_before(2), Some(0));
+    /// assert_eq!(bits.next_one_after(0), Some(2));
+    /// ```
+    #[inline(always)]
+    pub fn prev_one_before(&self, i: usize) -> Option<usize> {
+        Positions::<O>::new(self.0, 0..i.min(self.len()), true).next_back()
+    }
+
+    /// Read the unsigned field of `width` bits at the bit `offset` in the bit order
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the field is out of the bits
+    /// # Examples
+    /// ```
+    /// # use xbits::AsBits;
+    /// let header = [0b1010_0000, 0b0000_0101];
+    /// assert_eq!(header.as_bits().read_uint(3, 13), Ok(5));
+    /// assert_eq!(header.as_bits().read_int(0, 3), Ok(-3));
+    /// ```
+    #[inline(always)]
+    pub fn read_uint(&self, offset: usize, width: usize) -> Result<u64> {
+        self.0.bit_read_uint_in::<O>(offset, width)
+    }
+
+    /// Read the signed field of `width` bits at the bit `offset` in the bit order
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the field is out of the bits
+    #[inline(always)]
+    pub fn read_int(&self, offset: usize, width: usize) -> Result<i64> {
+        self.0.bit_read_int_in::<O>(offset, width)
+    }
+
+    /// View the bits in the range
+    /// # Panics
+    /// - if the range is out of the bits
+    #[inline(always)]
+    pub fn range(&self, range: std::ops::Range<usize>) -> RangeRef<'a, O> {
+        RangeRef::new(self.0, range)
+    }
 }
-impl std::ops::Index<usize> for BitsRef<'_> {
+impl<O: BitOrder> std::ops::Index<usize> for BitsRef<'_, O> {
     type Output = bool;
 
     #[inline(always)]
     fn index(&self, index: usize) -> &Self::Output {
-        if self.0.bit_get(index) { &true } else { &false }
+        if self.get(index) { &true } else { &false }
     }
 }
 
-impl std::fmt::Display for BitsRef<'_> {
-    #[inline(always)]
+impl<O: BitOrder> std::fmt::Display for BitsRef<'_, O> {
+    /// Format the bits of each byte in the bit order
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.bit_fmt())
+        let bytes = self.0.chunks(1).map(|b| {
+            b.bit_iter_in::<O>()
+                .map(|bit| if bit { '1' } else { '0' })
+                .collect::<String>()
+        });
+        write!(f, "{}", bytes.collect::<Vec<_>>().join(" "))
     }
 }
 
 /// A mutable wrapper to a byte array that allows for bit-level operations.
 /// It provides methods for shifting, bitwise operations, and reversing bits.
 /// It can be used to modify the underlying byte array directly.
+/// The bit order `O` applies to indexing, the shifts work on the big-endian value.
 #[derive(Debug, PartialEq, Eq)]
-pub struct BitsMut<'a>(pub &'a mut [u8]);
+pub struct BitsMut<'a, O: BitOrder = Msb0>(pub &'a mut [u8], pub PhantomData<O>);
 
-impl BitsMut<'_> {
+impl<'a, O: BitOrder> BitsMut<'a, O> {
+    /// Create a mutable view of the bytes, it replaces the tuple constructor `BitsMut(data)`
     #[inline(always)]
-    pub fn to_ref(&self) -> BitsRef {
-        BitsRef(self.0)
+    pub const fn new(data: &'a mut [u8]) -> Self {
+        BitsMut(data, PhantomData)
+    }
+
+    #[inline(always)]
+    pub fn to_ref(&self) -> BitsRef<'_, O> {
+        BitsRef::new(self.0)
     }
 
     #[inline(always)]
@@ -186,29 +354,59 @@ impl BitsMut<'_> {
 
     #[inline(always)]
     pub fn set(self, index: usize, value: bool) -> Self {
-        self.0.bit_set(index, value);
+        self.0.bit_set_in::<O>(index, value);
         self
     }
 
-    // pub fn range(&self, range: std::ops::Range<usize>) -> RangeMut<'_> {
-    //     RangeMut {
-    //         data: self.0,
-    //         range,
-    //     }
-    // }
+    /// Write the unsigned field of `width` bits at the bit `offset` in the bit order
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the field is out of the bits
+    /// - `Error::Overflow` if the value does not fit in `width` bits
+    /// # Examples
+    /// ```
+    /// # use xbits::AsBitsMut;
+    /// let mut data = [0_u8; 2];
+    /// let mut bits = data.as_bits_mut();
+    /// bits.write_uint(0, 3, 0b101).unwrap();
+    /// bits.write_int(3, 13, -1).unwrap();
+    /// assert_eq!(bits.to_ref().read_uint(0, 3), Ok(0b101));
+    /// assert_eq!(data, [0b1011_1111, 0b1111_1111]);
+    /// ```
+    #[inline(always)]
+    pub fn write_uint(&mut self, offset: usize, width: usize, value: u64) -> Result<()> {
+        self.0.bit_write_uint_in::<O>(offset, width, value)
+    }
+
+    /// Write the signed field of `width` bits at the bit `offset` in the bit order
+    /// # Errors
+    /// - `Error::UnexpectedEnd` if the field is out of the bits
+    /// - `Error::Overflow` if the value does not fit in `width` bits
+    #[inline(always)]
+    pub fn write_int(&mut self, offset: usize, width: usize, value: i64) -> Result<()> {
+        self.0.bit_write_int_in::<O>(offset, width, value)
+    }
+
+    /// View the mutable bits in the range
+    /// # Panics
+    /// - if the range is out of the bits
+    #[inline(always)]
+    pub fn range(&mut self, range: std::ops::Range<usize>) -> RangeMut<'_, O> {
+        RangeMut::new(self.0, range)
+    }
 }
 
-impl std::fmt::Display for BitsMut<'_> {
+impl<O: BitOrder> std::fmt::Display for BitsMut<'_, O> {
     #[inline(always)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.bit_fmt())
+        self.to_ref().fmt(f)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::{AsBits, AsBitsMut};
-    use crate::ToBits;
+    use crate::{Lsb0, ToBits};
 
     #[test]
     fn test_xbits() {
@@ -265,4 +463,26 @@ mod tests {
         assert_eq!(buf.as_bits_mut().fill(false).to_ref().all_zero(), true);
         assert_eq!(buf.as_bits_mut().fill(true).to_ref().all_one(), true);
     }
+
+    #[test]
+    fn test_bit_order() {
+        let mut buf = [0b0000_0001_u8, 0b1000_0000];
+        assert_eq!(buf.as_bits_in::<Lsb0>().to_string(), "10000000 00000001");
+        assert_eq!(buf.as_bits_in::<Lsb0>()[0], true);
+        assert_eq!(buf.as_bits_in::<Lsb0>()[15], true);
+        assert_eq!(
+            buf.as_bits_in::<Lsb0>().chunks::<u8>(4).collect::<Vec<_>>(),
+            [0b0001, 0b0000, 0b0000, 0b1000]
+        );
+
+        buf.as_bits_mut_in::<Lsb0>().set(0, false).set(8, true);
+        assert_eq!(buf, [0b0000_0000, 0b1000_0001]);
+        assert_eq!(buf.as_bits_mut_in::<Lsb0>().shr(1).to_ref().get(14), true);
+        assert_eq!(buf, [0b0000_0000, 0b0100_0000]);
+        assert_eq!(buf.as_bits_in::<Lsb0>().next_one_after(usize::MAX), None);
+        assert_eq!(
+            buf.as_bits_in::<Lsb0>().prev_one_before(usize::MAX),
+            Some(14)
+        );
+    }
 }
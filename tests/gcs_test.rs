@@ -0,0 +1,167 @@
+#![cfg(test)]
+use xbits::GcsFilter;
+
+/// The BIP158 test vectors in the layout of `testnet-19.json`, the first row is the header
+/// # Reference
+///   <https://github.com/bitcoin/bips/blob/master/bip-0158/testnet-19.json>
+const TESTNET_19: &str = include_str!("data/testnet-19.json");
+
+/// A row of the test vectors
+struct Vector {
+    height: u64,
+    block_hash: String,
+    block: Vec<u8>,
+    prev_scripts: Vec<Vec<u8>>,
+    filter: String,
+}
+
+#[test]
+fn test_bip158_testnet_19() {
+    let vectors = vectors();
+    assert!(!vectors.is_empty());
+    for v in &vectors {
+        let elements = elements(&v.block, &v.prev_scripts);
+        let key = key(&v.block_hash);
+        let filter = GcsFilter::build(&elements, GcsFilter::BASIC_P, GcsFilter::BASIC_M, key);
+        assert_eq!(
+            hex::encode(filter.to_bytes()),
+            v.filter,
+            "block {}",
+            v.height
+        );
+        assert!(elements.iter().all(|e| filter.match_one(e)));
+
+        let bytes = hex::decode(&v.filter).unwrap();
+        let parsed = GcsFilter::from_bytes(&bytes, GcsFilter::BASIC_P, GcsFilter::BASIC_M, key);
+        assert_eq!(parsed, Ok(filter), "block {}", v.height);
+    }
+}
+
+#[test]
+fn test_bip158_elements() {
+    // a spent duplicate of an output script and an empty spent script add no elements
+    let genesis = vectors().remove(0);
+    let script = elements(&genesis.block, &[]).remove(0);
+    let elements = elements(&genesis.block, &[script.clone(), vec![]]);
+    let key = key(&genesis.block_hash);
+    let filter = GcsFilter::build(&elements, GcsFilter::BASIC_P, GcsFilter::BASIC_M, key);
+    assert_eq!(hex::encode(filter.to_bytes()), genesis.filter);
+}
+
+/// The SipHash key of the block, the block hash in the internal byte order
+fn key(block_hash: &str) -> [u8; 16] {
+    let mut hash = hex::decode(block_hash).unwrap();
+    hash.reverse();
+    hash[..16].try_into().unwrap()
+}
+
+/// The basic filter elements of a block: the output scripts except the empty and OP_RETURN ones,
+/// then the non-empty scripts of the outputs the block spends
+fn elements(block: &[u8], prev_scripts: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let outputs = output_scripts(block)
+        .into_iter()
+        .filter(|s| !s.is_empty() && s[0] != 0x6a);
+    let prev = prev_scripts.iter().filter(|s| !s.is_empty()).cloned();
+    outputs.chain(prev).collect()
+}
+
+/// The output scripts of the transactions of a serialized block
+fn output_scripts(block: &[u8]) -> Vec<Vec<u8>> {
+    let mut r = Reader(&block[80..]);
+    let mut scripts = Vec::new();
+    for _ in 0..r.compact() {
+        r.take(4);
+        // segwit marker and flag
+        let witness = r.0[..2] == [0x00, 0x01];
+        if witness {
+            r.take(2);
+        }
+        let inputs = r.compact();
+        for _ in 0..inputs {
+            r.take(36);
+            r.script();
+            r.take(4);
+        }
+        for _ in 0..r.compact() {
+            r.take(8);
+            scripts.push(r.script().to_vec());
+        }
+        if witness {
+            for _ in 0..inputs {
+                for _ in 0..r.compact() {
+                    r.script();
+                }
+            }
+        }
+        r.take(4);
+    }
+    assert!(r.0.is_empty());
+    scripts
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> &'a [u8] {
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        head
+    }
+
+    fn compact(&mut self) -> usize {
+        let n = match self.take(1)[0] {
+            0xfd => 2,
+            0xfe => 4,
+            0xff => 8,
+            b => return b as usize,
+        };
+        let bytes = self.take(n);
+        bytes.iter().rev().fold(0, |v, &b| v << 8 | b as usize)
+    }
+
+    fn script(&mut self) -> &'a [u8] {
+        let len = self.compact();
+        self.take(len)
+    }
+}
+
+/// The rows of the test vectors, the fixture holds only strings, integers and arrays of strings
+fn vectors() -> Vec<Vector> {
+    let rows = TESTNET_19.trim().strip_prefix('[').unwrap();
+    let rows = rows.strip_suffix(']').unwrap();
+    let mut vectors = Vec::new();
+    for row in rows.split("\n").map(str::trim).filter(|r| !r.is_empty()) {
+        let row = row.trim_end_matches(',');
+        let row = &row[1..row.len() - 1];
+        // the header row has only one column
+        let Some((height, rest)) = row.split_once(',') else {
+            continue;
+        };
+        let Ok(height) = height.parse() else {
+            continue;
+        };
+        let (strings, prev) = rest.split_once('[').unwrap();
+        let (prev, rest) = prev.split_once(']').unwrap();
+        // the notes may hold commas, they are the last column
+        let strings: Vec<&str> = strings
+            .split(',')
+            .chain(rest.split(','))
+            .filter(|s| !s.trim().is_empty())
+            .collect();
+        let unquote = |s: &str| s.trim().trim_matches('"').to_string();
+        let prev_scripts = prev
+            .split(',')
+            .map(unquote)
+            .filter(|s| !s.is_empty() || prev.contains("\"\""))
+            .map(|s| hex::decode(s).unwrap())
+            .collect();
+        vectors.push(Vector {
+            height,
+            block_hash: unquote(strings[0]),
+            block: hex::decode(unquote(strings[1])).unwrap(),
+            prev_scripts,
+            filter: unquote(strings[3]),
+        });
+    }
+    vectors
+}
@@ -1,4 +1,5 @@
 #![cfg(test)]
+#![allow(clippy::useless_vec, clippy::unusual_byte_groupings)]
 
 use xbits::core::{BitIterator, FromBits};
 
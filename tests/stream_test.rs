@@ -0,0 +1,103 @@
+#![cfg(test)]
+use hex::FromHex;
+use xbits::core::{BitIterator, FromBits};
+use xbits::{BitOrder, BitReader, BitWriter, Lsb0, Msb0};
+
+#[test]
+fn test_reader_chunks() {
+    for entropy in ENTROPY_LIST {
+        let data = Vec::from_hex(entropy).expect("entropy");
+        for n in [1, 5, 6, 11, 17, 32, 64] {
+            let chunks: Vec<u64> = BitReader::new(&data[..])
+                .chunks(n)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(chunks, data.bit_chunks::<u64>(n).collect::<Vec<_>>());
+
+            let mut reader = BitReader::new(&data[..]);
+            let bits: Vec<bool> = std::iter::from_fn(|| reader.read_bit().ok()).collect();
+            assert_eq!(bits, data.bit_iter().collect::<Vec<_>>());
+        }
+    }
+}
+
+/// The chunks of the reader after `skip` bits against `bit_chunks_in` of the bits after `skip`
+fn check_wide_chunks<O: BitOrder>(order: O, data: &[u8], skip: usize, n: usize) {
+    let mut reader = BitReader::with_order(data, order);
+    reader.skip_bits(skip as u64).unwrap();
+    let chunks: Vec<u64> = reader.chunks(n).collect::<Result<_, _>>().unwrap();
+
+    let rest = Vec::from_bits_in::<O, _>(data.bit_iter_in::<O>().skip(skip));
+    let count = (data.len() * 8 - skip).div_ceil(n);
+    let expected: Vec<u64> = rest.bit_chunks_in::<O, u64>(n).take(count).collect();
+    assert_eq!(chunks, expected, "skip {skip} n {n}");
+}
+
+#[test]
+fn test_reader_wide_chunks() {
+    for entropy in ENTROPY_LIST {
+        let data = Vec::from_hex(entropy).expect("entropy");
+        for n in 33..=64 {
+            for skip in [0, 3, 13] {
+                if skip <= data.len() * 8 {
+                    check_wide_chunks(Msb0, &data, skip, n);
+                    check_wide_chunks(Lsb0, &data, skip, n);
+                }
+            }
+        }
+    }
+}
+
+const ENTROPY_LIST: &[&str] = &[
+    "0d5403ae28c460ddfefc967af18962b44d7c6fb23dcfc79a0f288a62895080f8",
+    "99c8a1870ef86dd6137f86a0fb3b688c57525ae8c9f9e0afdbc98d65",
+    "5174bb1dddfc6e2fef4e47df6fcc046a48d195b9",
+    "97da2890de2af07b87f23769",
+    "33448f2a",
+    "",
+];
+
+#[test]
+fn test_writer_chunks() {
+    for entropy in ENTROPY_LIST {
+        let data = Vec::from_hex(entropy).expect("entropy");
+        for n in [1, 5, 6, 11, 17, 32] {
+            let chunks: Vec<u64> = data.bit_chunks(n).collect();
+            let mut writer = BitWriter::new(vec![]);
+            for &v in &chunks {
+                writer.write_bits(v, n).unwrap();
+            }
+            let (bytes, len) = writer.into_inner().unwrap();
+            assert_eq!(bytes, Vec::from_bit_chunks(chunks.iter().copied(), n));
+            assert_eq!(len as usize, chunks.len() * n);
+        }
+    }
+}
+
+#[test]
+fn test_lsb0_chunks() {
+    for entropy in ENTROPY_LIST {
+        let data = Vec::from_hex(entropy).expect("entropy");
+        for n in [1, 5, 6, 11, 17, 32] {
+            let chunks: Vec<u64> = BitReader::with_order(&data[..], Lsb0)
+                .chunks(n)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                chunks,
+                data.bit_chunks_in::<Lsb0, u64>(n).collect::<Vec<_>>()
+            );
+
+            let mut writer = BitWriter::with_order(vec![], Lsb0);
+            for &v in &chunks {
+                writer.write_bits(v, n).unwrap();
+            }
+            let (bytes, _) = writer.into_inner().unwrap();
+            let expected = Vec::from_bit_chunks_in::<Lsb0, _, _>(chunks.iter().copied(), n);
+            assert_eq!(bytes, expected);
+            assert_eq!(bytes[..data.len()], data[..]);
+        }
+        let bits: Vec<bool> = data.bit_iter_in::<Lsb0>().collect();
+        assert_eq!(Vec::from_bits_in::<Lsb0, _>(bits.into_iter()), data);
+    }
+}
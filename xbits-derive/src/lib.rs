@@ -0,0 +1,161 @@
+//! Derive macros of `xbits`, enabled by its `derive` feature
+//!
+//! # Examples
+//! ```
+//! use xbits::{AsBits, BitPack};
+//!
+//! #[derive(BitPack, Debug, PartialEq)]
+//! struct Header {
+//!     #[bits(3)]
+//!     version: u8,
+//!     #[bits(1)]
+//!     testnet: bool,
+//!     #[bits(12)]
+//!     account: u16,
+//! }
+//!
+//! let header = Header { version: 5, testnet: true, account: 7 };
+//! let bits = header.to_bits();
+//! assert_eq!(bits.as_bytes(), [0b1011_0000, 0b0000_0111]);
+//! assert_eq!(Header::from_bits(&bits.as_bits()), Ok(header));
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Member, Result, Type, parse_macro_input};
+
+/// Derive `xbits::BitPack` for a struct of `bool` and integer fields up to 64 bits.
+/// The fields are packed in the declared order, `#[bits(n)]` sets the width of a field,
+/// the default width is the bits of its type.
+#[proc_macro_derive(BitPack, attributes(bits))]
+pub fn derive_bit_pack(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The kind of a field type
+enum Kind {
+    Bool,
+    Unsigned(usize),
+    Signed(usize),
+}
+
+impl Kind {
+    /// The kind of a primitive type
+    fn of(ty: &Type) -> Option<Self> {
+        let Type::Path(path) = ty else { return None };
+        let name = path.path.get_ident()?.to_string();
+        let kind = match name.as_str() {
+            "bool" => Kind::Bool,
+            "u8" => Kind::Unsigned(8),
+            "u16" => Kind::Unsigned(16),
+            "u32" => Kind::Unsigned(32),
+            "u64" | "usize" => Kind::Unsigned(64),
+            "i8" => Kind::Signed(8),
+            "i16" => Kind::Signed(16),
+            "i32" => Kind::Signed(32),
+            "i64" | "isize" => Kind::Signed(64),
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// The bits of the type
+    fn bits(&self) -> usize {
+        match self {
+            Kind::Bool => 1,
+            Kind::Unsigned(n) | Kind::Signed(n) => *n,
+        }
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "BitPack supports structs only",
+        ));
+    };
+
+    let (mut pushes, mut reads, mut offset) = (vec![], vec![], 0_usize);
+    for (i, field) in data.fields.iter().enumerate() {
+        let (ty, member) = (&field.ty, field_member(field.ident.clone(), i));
+        let kind = Kind::of(ty).ok_or_else(|| {
+            Error::new_spanned(
+                ty,
+                "BitPack fields must be `bool` or integers up to 64 bits",
+            )
+        })?;
+        let width = field_width(field, &kind)?;
+
+        pushes.push(quote! { bits.push_bits(self.#member as u64, #width); });
+        reads.push(match kind {
+            Kind::Bool => quote! { bits.read_uint(#offset, 1)? != 0 },
+            Kind::Unsigned(_) => quote! { bits.read_uint(#offset, #width)? as #ty },
+            Kind::Signed(_) => quote! { bits.read_int(#offset, #width)? as #ty },
+        });
+        offset += width;
+    }
+
+    let value = match &data.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| &f.ident);
+            quote! { Self { #(#names: #reads),* } }
+        }
+        Fields::Unnamed(_) => quote! { Self(#(#reads),*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::xbits::BitPack for #name #ty_generics #where_clause {
+            const BITS: usize = #offset;
+
+            fn to_bits(&self) -> ::xbits::Bits {
+                let mut bits = ::xbits::Bits::new(0);
+                #(#pushes)*
+                bits
+            }
+
+            fn from_bits(bits: &::xbits::BitsRef) -> ::xbits::Result<Self> {
+                if bits.len() < #offset {
+                    return Err(::xbits::Error::UnexpectedEnd);
+                }
+                Ok(#value)
+            }
+        }
+    })
+}
+
+fn field_member(ident: Option<syn::Ident>, index: usize) -> Member {
+    match ident {
+        Some(ident) => Member::Named(ident),
+        None => Member::Unnamed(index.into()),
+    }
+}
+
+/// The width of `#[bits(n)]`, 1 <= n <= bits of the type
+fn field_width(field: &syn::Field, kind: &Kind) -> Result<usize> {
+    let mut width = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("bits"))
+    {
+        if width.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `#[bits]` attribute"));
+        }
+        let n: syn::LitInt = attr.parse_args()?;
+        let value: usize = n.base10_parse()?;
+        if !(1..=kind.bits()).contains(&value) {
+            let message = format!("`#[bits]` width must be in `1..={}`", kind.bits());
+            return Err(Error::new_spanned(n, message));
+        }
+        width = Some(value);
+    }
+    Ok(width.unwrap_or(kind.bits()))
+}
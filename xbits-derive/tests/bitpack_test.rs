@@ -0,0 +1,97 @@
+use xbits::core::BitIterator;
+use xbits::{AsBits, BitPack, Error, FromBits};
+
+#[derive(BitPack, Debug, PartialEq)]
+struct Mnemonic {
+    #[bits(11)]
+    first: u16,
+    #[bits(11)]
+    second: u16,
+    #[bits(11)]
+    third: u32,
+}
+
+#[derive(BitPack, Debug, PartialEq)]
+struct Metadata {
+    #[bits(1)]
+    hardened: bool,
+    #[bits(5)]
+    offset: i8,
+    version: u8,
+    #[bits(48)]
+    timestamp: u64,
+    #[bits(9)]
+    delta: i64,
+}
+
+#[derive(BitPack, Debug, PartialEq)]
+struct Pair(#[bits(4)] u8, bool);
+
+#[derive(BitPack, Debug, PartialEq)]
+struct Empty;
+
+#[test]
+fn test_chunks_layout() {
+    let words = Mnemonic {
+        first: 2047,
+        second: 0,
+        third: 1234,
+    };
+    assert_eq!(Mnemonic::BITS, 33);
+    let bits = words.to_bits();
+    assert_eq!(bits.len(), 33);
+    assert_eq!(
+        bits.as_bytes(),
+        Vec::from_bit_chunks([2047_u16, 0, 1234].into_iter(), 11)
+    );
+    let chunks: Vec<u16> = bits.as_bytes().bit_chunks(11).take(3).collect();
+    assert_eq!(chunks, [2047, 0, 1234]);
+    assert_eq!(Mnemonic::from_bits(&bits.as_bits()), Ok(words));
+}
+
+#[test]
+fn test_round_trip() {
+    let metadata = Metadata {
+        hardened: true,
+        offset: -16,
+        version: 0xa5,
+        timestamp: 0x0123_4567_89ab,
+        delta: 255,
+    };
+    assert_eq!(Metadata::BITS, 1 + 5 + 8 + 48 + 9);
+    let bits = metadata.to_bits();
+    assert_eq!(bits.as_bytes()[0], 0b1100_0010);
+    assert_eq!(Metadata::from_bits(&bits.as_bits()), Ok(metadata));
+
+    // the lowest bits of the values, sign extended when unpacked
+    let truncated = Metadata {
+        hardened: false,
+        offset: 17,
+        version: 0,
+        timestamp: u64::MAX,
+        delta: -257,
+    };
+    let unpacked = Metadata::from_bits(&truncated.to_bits().as_bits()).unwrap();
+    assert_eq!(unpacked.offset, -15);
+    assert_eq!(unpacked.timestamp, 0xffff_ffff_ffff);
+    assert_eq!(unpacked.delta, 255);
+
+    let pair = Pair(0b1010, true);
+    assert_eq!(pair.to_bits().as_bytes(), [0b1010_1000]);
+    assert_eq!(
+        Pair::from_bits(&[0b0011_1111].as_bits()),
+        Ok(Pair(0b0011, true))
+    );
+
+    assert_eq!(Empty::BITS, 0);
+    assert_eq!(Empty::from_bits(&[].as_bits()), Ok(Empty));
+}
+
+#[test]
+fn test_short() {
+    assert_eq!(
+        Mnemonic::from_bits(&[0xff; 4].as_bits()),
+        Err(Error::UnexpectedEnd)
+    );
+    assert_eq!(Pair::from_bits(&[].as_bits()), Err(Error::UnexpectedEnd));
+}